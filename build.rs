@@ -1,3 +1,5 @@
+#![allow(clippy::to_string_in_format_args)]
+
 use std::println;
 
 use clap_complete::generate_to;
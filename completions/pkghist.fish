@@ -1,13 +1,219 @@
-complete -c pkghist -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	}"
-complete -c pkghist -s l -l logfile -d 'Specify a logfile' -r
-complete -c pkghist -s L -l limit -d 'How many versions to go back in report. [limit > 0]' -r
-complete -c pkghist -l first -d 'Output the first \'n\' pacman events' -r
-complete -c pkghist -l last -d 'Output the last \'n\' pacman events' -r
-complete -c pkghist -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
-complete -c pkghist -s r -l with-removed -d 'Include packages that are currently uninstalled'
-complete -c pkghist -s R -l removed-only -d 'Only output packages that are currently uninstalled'
-complete -c pkghist -l no-colors -d 'Disable colored output'
-complete -c pkghist -l no-details -d 'Only output the package names'
-complete -c pkghist -s x -l exclude -d 'If set, every filter result will be excluded.'
-complete -c pkghist -s h -l help -d 'Print help'
-complete -c pkghist -s V -l version -d 'Print version'
+complete -c pkghist -n "__fish_use_subcommand" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_use_subcommand" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_use_subcommand" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_use_subcommand" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_use_subcommand" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_use_subcommand" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_use_subcommand" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_use_subcommand" -s L -l limit -d 'How many versions to go back in report. [limit > 0]' -r
+complete -c pkghist -n "__fish_use_subcommand" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_use_subcommand" -l columns -d 'The columns of the compact output, e.g. \'package,date,action,from,to,duration\'' -r -f -a "{package	,host	,date	,action	,version	,from	,to	,duration	}"
+complete -c pkghist -n "__fish_use_subcommand" -l first -d 'Output the first \'n\' pacman events' -r
+complete -c pkghist -n "__fish_use_subcommand" -l last -d 'Output the last \'n\' pacman events' -r
+complete -c pkghist -n "__fish_use_subcommand" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_use_subcommand" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_use_subcommand" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_use_subcommand" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_use_subcommand" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_use_subcommand" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_use_subcommand" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_use_subcommand" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_use_subcommand" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_use_subcommand" -l no-details -d 'Only output the package names'
+complete -c pkghist -n "__fish_use_subcommand" -l details -d 'Output the package details, even if \'no-details\' is set in the config file'
+complete -c pkghist -n "__fish_use_subcommand" -l durations -d 'Output how long every version stayed installed and the lifetime of each package'
+complete -c pkghist -n "__fish_use_subcommand" -s x -l exclude -d 'If set, every filter result will be excluded.'
+complete -c pkghist -n "__fish_use_subcommand" -l include -d 'Include the filter results, even if the query excludes them'
+complete -c pkghist -n "__fish_use_subcommand" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_use_subcommand" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_use_subcommand" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_use_subcommand" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_use_subcommand" -s V -l version -d 'Print version'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "audit" -d 'Report partial upgrades (databases synced without a full system upgrade) and churning packages'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "activity" -d 'Show a calendar heatmap of the number of pacman events per day'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "fleet" -d 'Show which hosts (see --host) run which version of every package'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "advisories" -d 'Report when versions affected by security advisories were installed and when they were fixed'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "reboot" -d 'Report kernels, glibc, systemd and other packages changed since the current boot (see --boots)'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "kernel" -d 'Report changes of kernels, modules, drivers and other boot-critical packages per transaction'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "run" -d 'Run a named query defined in the config file'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "tui" -d 'Browse the package history in an interactive terminal UI'
+complete -c pkghist -n "__fish_use_subcommand" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l churn-window -d 'Report packages flip-flopping at most \'days\' apart [default: 7]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from audit" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from activity" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from fleet" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from advisories" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from reboot" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s p -l package -d 'Track this package as well' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from kernel" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from run" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s o -l output-format -d 'Select the output format' -r -f -a "{json	,plain	,compact	,timeline	,log	,markdown	,html	}"
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l format-template -d 'Output every event using a template, e.g. \'{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}\' [fields: package, date, action, version, from, to, host]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s l -l logfile -d 'Specify a logfile [default: /var/log/pacman.log, depends on --source]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l host -d 'Read the logfile of another machine, every event is tagged with the host\'s name [repeatable]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l boots -d 'Read the boots exported by \'journalctl --list-boots -o json\' and mark changes pending a reboot' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l source -d 'Select the package manager that wrote the logfile [default: pacman]' -r -f -a "{pacman	,apt	,dpkg	,dnf	,apk	,xbps	}"
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s c -l config -d 'Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l color -d 'Color the output, \'auto\' only colors a terminal and respects NO_COLOR [default: auto]' -r -f -a "{auto	,always	,never	}"
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s a -l after -d 'Only consider events that occurred after \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s b -l before -d 'Only consider events that occurred before \'date\' [Format: "YYYY-MM-DD HH:MM"]' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s e -l expression -d 'Only consider events matching a boolean expression (e.g. "name~\'^python\' and action=upgraded and date>2024-01-01 and not name~\'-git$\'")' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s A -l action -d 'Only consider events with this action' -r -f -a "{installed	,reinstalled	,upgraded	,downgraded	,removed	}"
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l filter-file -d 'Read additional filters from a file, one per line (\'#\' starts a comment)' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s f -l filter-set -d 'Add the filters of a filter set defined in the config file' -r
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s r -l with-removed -d 'Include packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s R -l removed-only -d 'Only output packages that are currently uninstalled'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l no-colors -d 'Disable colored output, like --color never'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l exact -d 'Match the filters against the whole package name instead of as regular expressions'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -l glob -d 'Match the filters as shell globs against the whole package name (e.g. \'python-*\')'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s i -l ignore-case -d 'Ignore the case when matching filters'
+complete -c pkghist -n "__fish_seen_subcommand_from tui" -s h -l help -d 'Print help'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "audit" -d 'Report partial upgrades (databases synced without a full system upgrade) and churning packages'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "activity" -d 'Show a calendar heatmap of the number of pacman events per day'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "fleet" -d 'Show which hosts (see --host) run which version of every package'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "advisories" -d 'Report when versions affected by security advisories were installed and when they were fixed'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "reboot" -d 'Report kernels, glibc, systemd and other packages changed since the current boot (see --boots)'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "kernel" -d 'Report changes of kernels, modules, drivers and other boot-critical packages per transaction'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "run" -d 'Run a named query defined in the config file'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "tui" -d 'Browse the package history in an interactive terminal UI'
+complete -c pkghist -n "__fish_seen_subcommand_from help; and not __fish_seen_subcommand_from audit; and not __fish_seen_subcommand_from activity; and not __fish_seen_subcommand_from fleet; and not __fish_seen_subcommand_from advisories; and not __fish_seen_subcommand_from reboot; and not __fish_seen_subcommand_from kernel; and not __fish_seen_subcommand_from run; and not __fish_seen_subcommand_from tui; and not __fish_seen_subcommand_from help" -f -a "help" -d 'Print this message or the help of the given subcommand(s)'
@@ -1,16 +1,90 @@
 use std::fmt;
+use std::io::ErrorKind;
+
+/// Exit codes of the `pkghist` binary.
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    /// No package matched the query, or a report found nothing
+    pub const NO_MATCHES: i32 = 1;
+    /// The logfile could not be opened or parsed
+    pub const UNREADABLE_LOGFILE: i32 = 2;
+    /// Invalid command line arguments, configuration file, query or template
+    pub const INVALID_ARGUMENTS: i32 = 3;
+    /// Any other error, e.g. failing to write the output
+    pub const FAILURE: i32 = 4;
+    /// The reading end of the output was closed (128 + SIGPIPE, like a shell reports it)
+    pub const BROKEN_PIPE: i32 = 141;
+}
+
+/// A position in a file, e.g. of a parse failure.
+#[derive(Debug, PartialEq)]
+pub struct Position {
+    pub path: String,
+    /// `None` if the line is unknown
+    pub line: Option<usize>,
+}
+
+/*
+ * Points at the line like compilers do, e.g. pkghist.toml:3
+ */
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}", self.path, line),
+            None => write!(f, "{}", self.path),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub enum ErrorDetail {
     IOError { msg: String },
     InvalidFormat,
     InvalidAction,
+    InvalidVersion { version: String },
     FormattingError { msg: String },
+    InvalidConfig { msg: String },
+    InvalidTemplate { msg: String },
+    UnreadableLogfile { path: String, msg: String },
+    InvalidLogfile { at: Position, msg: String },
+    InvalidConfigFile { at: Position, msg: String },
+    UnreadableConfigFile { path: String, msg: String },
+    UnreadableFilterFile { path: String, msg: String },
+    NoMatches,
+    BrokenPipe,
 }
 
 impl fmt::Display for ErrorDetail {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+        match self {
+            ErrorDetail::IOError { msg } => write!(f, "I/O error: {}", msg),
+            ErrorDetail::InvalidFormat => write!(f, "Invalid format"),
+            ErrorDetail::InvalidAction => write!(
+                f,
+                "Invalid action, expected one of installed, reinstalled, upgraded, downgraded, removed"
+            ),
+            ErrorDetail::InvalidVersion { version } => write!(f, "Invalid version '{}'", version),
+            ErrorDetail::FormattingError { msg } => {
+                write!(f, "Unable to format the output: {}", msg)
+            }
+            ErrorDetail::InvalidConfig { msg } => write!(f, "Invalid configuration: {}", msg),
+            ErrorDetail::InvalidTemplate { msg } => write!(f, "Invalid template: {}", msg),
+            ErrorDetail::UnreadableLogfile { path, msg } => {
+                write!(f, "Unable to open {}: {}", path, msg)
+            }
+            ErrorDetail::InvalidLogfile { at, msg } => write!(f, "Unable to parse {}: {}", at, msg),
+            ErrorDetail::InvalidConfigFile { at, msg } => {
+                write!(f, "Invalid configuration {}: {}", at, msg)
+            }
+            ErrorDetail::UnreadableConfigFile { path, msg } => {
+                write!(f, "Unable to open the configuration {}: {}", path, msg)
+            }
+            ErrorDetail::UnreadableFilterFile { path, msg } => {
+                write!(f, "Unable to open the filter file {}: {}", path, msg)
+            }
+            ErrorDetail::NoMatches => write!(f, "No matching packages"),
+            ErrorDetail::BrokenPipe => write!(f, "Broken pipe"),
+        }
     }
 }
 
@@ -31,8 +105,34 @@ impl Error {
             detail: error_detail,
         }
     }
+
+    pub fn detail(&self) -> &ErrorDetail {
+        &self.detail
+    }
+
+    /// The documented exit code of the `pkghist` binary for this error, see `exit_code`.
+    pub fn exit_code(&self) -> i32 {
+        match self.detail {
+            ErrorDetail::NoMatches => exit_code::NO_MATCHES,
+            ErrorDetail::UnreadableLogfile { .. } | ErrorDetail::InvalidLogfile { .. } => {
+                exit_code::UNREADABLE_LOGFILE
+            }
+            ErrorDetail::InvalidFormat
+            | ErrorDetail::InvalidAction
+            | ErrorDetail::InvalidVersion { .. }
+            | ErrorDetail::InvalidConfig { .. }
+            | ErrorDetail::InvalidConfigFile { .. }
+            | ErrorDetail::UnreadableConfigFile { .. }
+            | ErrorDetail::UnreadableFilterFile { .. }
+            | ErrorDetail::InvalidTemplate { .. } => exit_code::INVALID_ARGUMENTS,
+            ErrorDetail::BrokenPipe => exit_code::BROKEN_PIPE,
+            ErrorDetail::IOError { .. } | ErrorDetail::FormattingError { .. } => exit_code::FAILURE,
+        }
+    }
 }
 
+impl std::error::Error for Error {}
+
 impl From<std::fmt::Error> for Error {
     fn from(error: std::fmt::Error) -> Self {
         Error::new(ErrorDetail::FormattingError {
@@ -43,20 +143,22 @@ impl From<std::fmt::Error> for Error {
 
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
-        Error::new(ErrorDetail::IOError {
-            msg: format!("{:?} -> {}", error.kind(), error.to_string()),
-        })
+        match error.kind() {
+            ErrorKind::BrokenPipe => Error::new(ErrorDetail::BrokenPipe),
+            kind => Error::new(ErrorDetail::IOError {
+                msg: format!("{:?} -> {}", kind, error),
+            }),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::io::ErrorKind;
 
     #[test]
     fn should_convert_the_fmt_error() {
-        let e = std::fmt::Error::default();
+        let e = std::fmt::Error;
         let error = Error::from(e);
         assert_eq!(
             error.detail,
@@ -68,16 +170,39 @@ mod tests {
 
     #[test]
     fn should_convert_the_io_error() {
-        let e = std::io::Error::new(ErrorKind::BrokenPipe, "Pipe is broken");
+        let e = std::io::Error::new(ErrorKind::PermissionDenied, "Permission denied");
         let error = Error::from(e);
         assert_eq!(
             error.detail,
             ErrorDetail::IOError {
-                msg: String::from("BrokenPipe -> Pipe is broken")
+                msg: String::from("PermissionDenied -> Permission denied")
             }
         )
     }
 
+    #[test]
+    fn should_convert_the_broken_pipe_error() {
+        let e = std::io::Error::new(ErrorKind::BrokenPipe, "Pipe is broken");
+        let error = Error::from(e);
+        assert_eq!(error.detail, ErrorDetail::BrokenPipe);
+        assert_eq!(error.exit_code(), exit_code::BROKEN_PIPE)
+    }
+
+    #[test]
+    fn should_map_exit_codes() {
+        let no_matches = Error::new(ErrorDetail::NoMatches);
+        assert_eq!(no_matches.exit_code(), exit_code::NO_MATCHES);
+        let unreadable = Error::new(ErrorDetail::UnreadableLogfile {
+            path: String::from("/tmp/pacman.log"),
+            msg: String::from("No such file or directory"),
+        });
+        assert_eq!(unreadable.exit_code(), exit_code::UNREADABLE_LOGFILE);
+        let invalid = Error::new(ErrorDetail::InvalidTemplate {
+            msg: String::from("Unclosed section"),
+        });
+        assert_eq!(invalid.exit_code(), exit_code::INVALID_ARGUMENTS)
+    }
+
     #[test]
     fn should_set_the_error_message() {
         let error = Error::new(ErrorDetail::FormattingError {
@@ -85,7 +210,7 @@ mod tests {
         });
         assert_eq!(
             error.to_string(),
-            "Error: FormattingError { msg: \"This error is a test\" }"
+            "Error: Unable to format the output: This error is a test"
         )
     }
 
@@ -99,7 +224,47 @@ mod tests {
     fn should_format_correctly() {
         let error = Error::new(ErrorDetail::InvalidFormat);
         let str = format!("{}", error);
-        assert_eq!(str, String::from("Error: InvalidFormat"))
+        assert_eq!(str, String::from("Error: Invalid format"))
+    }
+
+    #[test]
+    fn should_format_with_path() {
+        let error = Error::new(ErrorDetail::UnreadableLogfile {
+            path: String::from("/tmp/pacman.log"),
+            msg: String::from("No such file or directory (os error 2)"),
+        });
+        assert_eq!(
+            error.to_string(),
+            "Error: Unable to open /tmp/pacman.log: No such file or directory (os error 2)"
+        )
+    }
+
+    #[test]
+    fn should_format_with_line() {
+        let error = Error::new(ErrorDetail::InvalidConfigFile {
+            at: Position {
+                path: String::from("pkghist.toml"),
+                line: Some(3),
+            },
+            msg: String::from("expected an equals"),
+        });
+        assert_eq!(
+            error.to_string(),
+            "Error: Invalid configuration pkghist.toml:3: expected an equals"
+        );
+        assert_eq!(error.exit_code(), exit_code::INVALID_ARGUMENTS);
+        let error = Error::new(ErrorDetail::InvalidLogfile {
+            at: Position {
+                path: String::from("boots.json"),
+                line: None,
+            },
+            msg: String::from("EOF while parsing"),
+        });
+        assert_eq!(
+            error.to_string(),
+            "Error: Unable to parse boots.json: EOF while parsing"
+        );
+        assert_eq!(error.exit_code(), exit_code::UNREADABLE_LOGFILE)
     }
 
     #[test]
@@ -0,0 +1,14 @@
+//! Query the local version history of pacman packages.
+//!
+//! Use [`HistoryQuery`] to read the package histories without printing anything,
+//! [`pkghist::run`] is the entry point of the `pkghist` binary.
+
+pub mod error;
+pub mod opt;
+pub mod pacman;
+pub mod pkghist;
+pub mod source;
+
+pub use crate::error::{Error, ErrorDetail};
+pub use crate::pkghist::query::HistoryQuery;
+pub use crate::pkghist::{Event, PackageHistory};
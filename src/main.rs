@@ -1,20 +1,32 @@
-use crate::error::Error;
 use std::env;
+use std::process;
 
-pub mod error;
-pub mod opt;
-pub mod pacman;
-pub mod pkghist;
+use pkghist::error::{exit_code, Error, ErrorDetail};
+use pkghist::opt;
 
-fn main() -> Result<(), Error> {
+fn main() {
     let args: Vec<String> = env::args().collect();
-    run(args)
+    let matches = opt::try_parse_args(&args).unwrap_or_else(|e| {
+        let _ = e.print();
+        if e.use_stderr() {
+            process::exit(exit_code::INVALID_ARGUMENTS)
+        }
+        process::exit(exit_code::SUCCESS)
+    });
+    if let Err(e) = run(&matches) {
+        match e.detail() {
+            ErrorDetail::NoMatches | ErrorDetail::BrokenPipe => {}
+            _ => eprintln!("{}", e),
+        }
+        process::exit(e.exit_code())
+    }
 }
 
-fn run(args: Vec<String>) -> Result<(), Error> {
-    let matches = opt::parse_args(&args);
-    let config = opt::Config::from_arg_matches(&matches);
-    pkghist::run(config)
+fn run(matches: &clap::ArgMatches) -> Result<(), Error> {
+    let config_file = opt::file::ConfigFile::load(matches.get_one::<String>("config"))?;
+    let mut config = opt::Config::from_arg_matches(matches, &config_file)?;
+    config.on_skipped_line = |skipped_line| eprintln!("{}", skipped_line);
+    pkghist::pkghist::run(config)
 }
 
 #[cfg(test)]
@@ -42,9 +54,9 @@ mod tests {
         .unwrap();
 
         let args = vec![String::from("pkghist"), String::from("-l"), file_name];
-        let r = run(args);
+        let r = run(&opt::parse_args(&args));
 
-        assert_eq!(r.is_ok(), true);
+        assert!(r.is_ok());
 
         fs::remove_file(file.path().unwrap()).unwrap()
     }
@@ -1,5 +1,5 @@
 use chrono::NaiveDateTime;
-use clap::{command, Arg, Command};
+use clap::{command, Arg, ArgAction, Command};
 
 pub fn build_cli() -> Command {
     command!(env!("CARGO_PKG_NAME"))
@@ -10,17 +10,64 @@ pub fn build_cli() -> Command {
                 .short('o')
                 .long("output-format")
                 .num_args(1)
-                .value_parser(["json", "plain", "compact"])
+                .value_parser(["json", "plain", "compact", "timeline", "log", "markdown", "html"])
                 .default_value("plain")
+                .global(true)
                 .help("Select the output format"),
         )
+        .arg(
+            Arg::new("format-template")
+                .long("format-template")
+                .value_name("template")
+                .num_args(1)
+                .conflicts_with("output-format")
+                .global(true)
+                .help("Output every event using a template, e.g. '{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}' [fields: package, date, action, version, from, to, host]"),
+        )
         .arg(
             Arg::new("logfile")
                 .short('l')
                 .long("logfile")
                 .value_name("FILE")
-                .help("Specify a logfile")
-                .default_value("/var/log/pacman.log")
+                .help("Specify a logfile [default: /var/log/pacman.log, depends on --source]")
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("host")
+                .long("host")
+                .value_name("name=path")
+                .action(ArgAction::Append)
+                .value_parser(validate_host)
+                .conflicts_with("logfile")
+                .global(true)
+                .help("Read the logfile of another machine, every event is tagged with the host's name [repeatable]"),
+        )
+        .arg(
+            Arg::new("boots")
+                .long("boots")
+                .value_name("FILE")
+                .num_args(1)
+                .conflicts_with("host")
+                .global(true)
+                .help("Read the boots exported by 'journalctl --list-boots -o json' and mark changes pending a reboot"),
+        )
+        .arg(
+            Arg::new("source")
+                .long("source")
+                .value_name("source")
+                .num_args(1)
+                .value_parser(["pacman", "apt", "dpkg", "dnf", "apk", "xbps"])
+                .global(true)
+                .help("Select the package manager that wrote the logfile [default: pacman]"),
+        )
+        .arg(
+            Arg::new("config")
+                .short('c')
+                .long("config")
+                .value_name("FILE")
+                .help("Read defaults from this file instead of /etc/pkghist.conf and $XDG_CONFIG_HOME/pkghist/config.toml")
+                .global(true)
                 .num_args(1),
         )
         .arg(
@@ -29,6 +76,7 @@ pub fn build_cli() -> Command {
                 .long("with-removed")
                 .num_args(0)
                 .conflicts_with("removed-only")
+                .global(true)
                 .help("Include packages that are currently uninstalled"),
         )
         .arg(
@@ -37,6 +85,7 @@ pub fn build_cli() -> Command {
                 .long("removed-only")
                 .num_args(0)
                 .conflicts_with("with-removed")
+                .global(true)
                 .help("Only output packages that are currently uninstalled"),
         )
         .arg(
@@ -50,21 +99,54 @@ pub fn build_cli() -> Command {
         .arg(
             Arg::new("no-colors")
                 .num_args(0)
-                .help("Disable colored output")
+                .help("Disable colored output, like --color never")
+                .conflicts_with("color")
+                .global(true)
                 .long("no-colors"),
         )
+        .arg(
+            Arg::new("color")
+                .long("color")
+                .value_name("when")
+                .num_args(1)
+                .value_parser(["auto", "always", "never"])
+                .global(true)
+                .help("Color the output, 'auto' only colors a terminal and respects NO_COLOR [default: auto]"),
+        )
         .arg(
             Arg::new("no-details")
                 .num_args(0)
                 .long("no-details")
+                .overrides_with("details")
                 .help("Only output the package names")
         )
+        .arg(
+            Arg::new("details")
+                .num_args(0)
+                .long("details")
+                .overrides_with("no-details")
+                .help("Output the package details, even if 'no-details' is set in the config file")
+        )
+        .arg(
+            Arg::new("columns")
+                .long("columns")
+                .value_name("columns")
+                .value_delimiter(',')
+                .value_parser(["package", "host", "date", "action", "version", "from", "to", "duration"])
+                .help("The columns of the compact output, e.g. 'package,date,action,from,to,duration'")
+        )
+        .arg(
+            Arg::new("durations")
+                .num_args(0)
+                .long("durations")
+                .help("Output how long every version stayed installed and the lifetime of each package")
+        )
         .arg(
             Arg::new("first")
                 .long("first")
                 .value_name("n")
                 .num_args(1)
-                .conflicts_with_all(&["filter", "last"])
+                .conflicts_with_all(["filter", "filter-file", "last"])
                 .help("Output the first 'n' pacman events")
                 .value_parser(validate_gt_0),
         )
@@ -73,7 +155,7 @@ pub fn build_cli() -> Command {
                 .long("last")
                 .value_name("n")
                 .num_args(1)
-                .conflicts_with("filter")
+                .conflicts_with_all(["filter", "filter-file"])
                 .help("Output the last 'n' pacman events")
                 .value_parser(validate_gt_0),
         )
@@ -86,24 +168,165 @@ pub fn build_cli() -> Command {
                     "Only consider events that occurred after 'date' [Format: \"YYYY-MM-DD HH:MM\"]",
                 )
                 .value_parser(validate_date)
+                .global(true)
+                .num_args(1),
+        )
+        .arg(
+            Arg::new("before")
+                .long("before")
+                .short('b')
+                .value_name("date")
+                .help(
+                    "Only consider events that occurred before 'date' [Format: \"YYYY-MM-DD HH:MM\"]",
+                )
+                .value_parser(validate_date)
+                .global(true)
                 .num_args(1),
         )
+        .arg(
+            Arg::new("expression")
+                .long("expression")
+                .short('e')
+                .value_name("expression")
+                .global(true)
+                .num_args(1)
+                .help("Only consider events matching a boolean expression \
+                (e.g. \"name~'^python' and action=upgraded and date>2024-01-01 and not name~'-git$'\")"),
+        )
+        .arg(
+            Arg::new("action")
+                .long("action")
+                .short('A')
+                .value_name("action")
+                .value_parser(["installed", "reinstalled", "upgraded", "downgraded", "removed"])
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Only consider events with this action"),
+        )
         .arg(
             Arg::new("exclude")
                 .long("exclude")
                 .short('x')
                 .num_args(0)
+                .overrides_with("include")
                 .help("If set, every filter result will be excluded.")
         )
         .arg(
-            Arg::new("filter")
-                .help("Filter the packages that should be searched for. \
-                Use regular expressions to specify the exact pattern to match \
-                (e.g. '^linux$' only matches the package 'linux')")
-                .num_args(0..),
+            Arg::new("include")
+                .long("include")
+                .num_args(0)
+                .overrides_with("exclude")
+                .help("Include the filter results, even if the query excludes them")
+        )
+        .arg(
+            Arg::new("exact")
+                .long("exact")
+                .num_args(0)
+                .conflicts_with("glob")
+                .global(true)
+                .help("Match the filters against the whole package name instead of as regular expressions")
+        )
+        .arg(
+            Arg::new("glob")
+                .long("glob")
+                .num_args(0)
+                .global(true)
+                .help("Match the filters as shell globs against the whole package name (e.g. 'python-*')")
+        )
+        .arg(
+            Arg::new("ignore-case")
+                .long("ignore-case")
+                .short('i')
+                .num_args(0)
+                .global(true)
+                .help("Ignore the case when matching filters")
+        )
+        .arg(
+            Arg::new("filter-file")
+                .long("filter-file")
+                .value_name("FILE")
+                .num_args(1)
+                .global(true)
+                .help("Read additional filters from a file, one per line ('#' starts a comment)")
+        )
+        .arg(
+            Arg::new("filter-set")
+                .short('f')
+                .long("filter-set")
+                .value_name("name")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("Add the filters of a filter set defined in the config file"),
+        )
+        .arg(filter_arg())
+        .subcommand(
+            Command::new("audit")
+                .about("Report partial upgrades (databases synced without a full system upgrade) and churning packages")
+                .arg(
+                    Arg::new("churn-window")
+                        .long("churn-window")
+                        .value_name("days")
+                        .num_args(1)
+                        .value_parser(validate_gt_0)
+                        .help("Report packages flip-flopping at most 'days' apart [default: 7]"),
+                )
+                .arg(filter_arg()),
+        )
+        .subcommand(
+            Command::new("activity")
+                .about("Show a calendar heatmap of the number of pacman events per day"),
+        )
+        .subcommand(
+            Command::new("fleet")
+                .about("Show which hosts (see --host) run which version of every package"),
+        )
+        .subcommand(
+            Command::new("advisories")
+                .about("Report when versions affected by security advisories were installed and when they were fixed")
+                .arg(
+                    Arg::new("file")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("The advisories as exported by https://security.archlinux.org/issues/all.json"),
+                ),
+        )
+        .subcommand(
+            Command::new("reboot")
+                .about("Report kernels, glibc, systemd and other packages changed since the current boot (see --boots)"),
+        )
+        .subcommand(
+            Command::new("kernel")
+                .about("Report changes of kernels, modules, drivers and other boot-critical packages per transaction")
+                .arg(
+                    Arg::new("package")
+                        .long("package")
+                        .short('p')
+                        .value_name("name")
+                        .action(ArgAction::Append)
+                        .help("Track this package as well"),
+                ),
+        )
+        .subcommand(
+            Command::new("run")
+                .about("Run a named query defined in the config file")
+                .arg(Arg::new("query").required(true).help("The name of the query")),
+        )
+        .subcommand(
+            Command::new("tui").about("Browse the package history in an interactive terminal UI"),
         )
 }
 
+/// The positional filters, subcommands take their own as they would be parsed as filters otherwise.
+fn filter_arg() -> Arg {
+    Arg::new("filter")
+        .help(
+            "Filter the packages that should be searched for. \
+            Use regular expressions to specify the exact pattern to match \
+            (e.g. '^linux$' only matches the package 'linux'), or see --exact and --glob",
+        )
+        .num_args(0..)
+}
+
 fn validate_gt_0(str: &str) -> Result<String, String> {
     match str.parse::<u32>() {
         Ok(l) => {
@@ -117,6 +340,13 @@ fn validate_gt_0(str: &str) -> Result<String, String> {
     }
 }
 
+fn validate_host(str: &str) -> Result<String, String> {
+    match str.split_once('=') {
+        Some((name, path)) if !name.is_empty() && !path.is_empty() => Ok(str.to_owned()),
+        _ => Err(String::from("Please provide a host as name=path")),
+    }
+}
+
 fn validate_date(str: &str) -> Result<String, String> {
     match NaiveDateTime::parse_from_str(str, "%Y-%m-%d %H:%M") {
         Ok(_) => Ok(str.to_owned()),
@@ -127,6 +357,7 @@ fn validate_date(str: &str) -> Result<String, String> {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
     #[test]
@@ -147,6 +378,13 @@ mod tests {
         assert_eq!(r.is_err(), true)
     }
 
+    #[test]
+    fn should_validate_host() {
+        assert!(validate_host("web1=/srv/logs/web1/pacman.log").is_ok());
+        assert!(validate_host("web1").is_err());
+        assert!(validate_host("=/srv/logs/web1/pacman.log").is_err())
+    }
+
     #[test]
     fn should_validate_date() {
         let d = validate_date("2019-10-02 12:30");
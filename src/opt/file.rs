@@ -0,0 +1,258 @@
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::error::{Error, ErrorDetail, Position};
+
+/// Defaults read from `/etc/pkghist.conf` and `$XDG_CONFIG_HOME/pkghist/config.toml`.
+///
+/// Every value is optional, command line arguments take precedence.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ConfigFile {
+    pub logfile: Option<String>,
+    pub source: Option<String>,
+    pub output_format: Option<String>,
+    pub no_colors: Option<bool>,
+    /// `auto`, `always` or `never`, like `--color`
+    pub color: Option<String>,
+    /// The color of every action, a name like `red` or `light-blue` or an ANSI color number
+    pub colors: BTreeMap<String, String>,
+    pub no_details: Option<bool>,
+    pub limit: Option<u32>,
+    pub with_removed: Option<bool>,
+    pub removed_only: Option<bool>,
+    /// The logfiles of other machines by host name, like `--host name=path`
+    pub hosts: BTreeMap<String, String>,
+    /// Tracked by the kernel report in addition to kernels, modules, drivers, systemd and mkinitcpio
+    pub boot_packages: Vec<String>,
+    pub filters: HashMap<String, Vec<String>>,
+    pub queries: HashMap<String, Query>,
+}
+
+/// A named query, run with `pkghist run <name>`.
+#[derive(Debug, Default, Deserialize, Clone, PartialEq)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Query {
+    pub filters: Vec<String>,
+    pub expression: Option<String>,
+    pub exclude: Option<bool>,
+    pub after: Option<String>,
+    pub before: Option<String>,
+    pub actions: Vec<String>,
+    pub output_format: Option<String>,
+    pub limit: Option<u32>,
+    pub first: Option<u32>,
+    pub last: Option<u32>,
+    pub with_removed: Option<bool>,
+    pub removed_only: Option<bool>,
+}
+
+impl ConfigFile {
+    pub fn from_file(path: &Path) -> Result<ConfigFile, Error> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            Error::new(ErrorDetail::UnreadableConfigFile {
+                path: path.display().to_string(),
+                msg: e.to_string(),
+            })
+        })?;
+        toml::from_str(&content).map_err(|e| {
+            Error::new(ErrorDetail::InvalidConfigFile {
+                at: Position {
+                    path: path.display().to_string(),
+                    line: e.line_col().map(|(line, _)| line + 1),
+                },
+                msg: e.to_string(),
+            })
+        })
+    }
+
+    /// Loads the given file or, if none is given, merges all existing default locations.
+    pub fn load(path: Option<&String>) -> Result<ConfigFile, Error> {
+        match path {
+            Some(p) => ConfigFile::from_file(Path::new(p)),
+            None => locations()
+                .iter()
+                .filter(|l| l.is_file())
+                .try_fold(ConfigFile::default(), |current, l| {
+                    Ok(current.merge(ConfigFile::from_file(l)?))
+                }),
+        }
+    }
+
+    /*
+     * Values set in other take precedence
+     */
+    fn merge(self, other: ConfigFile) -> ConfigFile {
+        let mut filters = self.filters;
+        filters.extend(other.filters);
+        let mut queries = self.queries;
+        queries.extend(other.queries);
+        let mut hosts = self.hosts;
+        hosts.extend(other.hosts);
+        let mut colors = self.colors;
+        colors.extend(other.colors);
+        let mut boot_packages = self.boot_packages;
+        boot_packages.extend(other.boot_packages);
+        ConfigFile {
+            logfile: other.logfile.or(self.logfile),
+            source: other.source.or(self.source),
+            output_format: other.output_format.or(self.output_format),
+            no_colors: other.no_colors.or(self.no_colors),
+            color: other.color.or(self.color),
+            colors,
+            no_details: other.no_details.or(self.no_details),
+            limit: other.limit.or(self.limit),
+            with_removed: other.with_removed.or(self.with_removed),
+            removed_only: other.removed_only.or(self.removed_only),
+            hosts,
+            boot_packages,
+            filters,
+            queries,
+        }
+    }
+}
+
+fn locations() -> Vec<PathBuf> {
+    let mut locations = vec![PathBuf::from("/etc/pkghist.conf")];
+    let config_home = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config")),
+    };
+    if let Some(dir) = config_home {
+        locations.push(dir.join("pkghist").join("config.toml"))
+    }
+    locations
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write;
+
+    use filepath::FilePath;
+
+    use super::*;
+
+    #[test]
+    fn should_read_config_file() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "logfile = \"/tmp/pacman.log\"\n\
+             output-format = \"compact\"\n\
+             no-colors = true\n\
+             limit = 3\n\
+             boot-packages = [\"grub\"]\n\
+             color = \"always\"\n\
+             [colors]\n\
+             removed = \"light-red\"\n\
+             [hosts]\n\
+             web1 = \"/srv/logs/web1/pacman.log\"\n\
+             [filters]\n\
+             kernel = [\"^linux\", \"-dkms$\"]\n\
+             [queries.last-changes]\n\
+             last = 20\n\
+             actions = [\"upgraded\", \"downgraded\"]"
+        )
+        .unwrap();
+
+        let config_file = ConfigFile::load(Some(&file_name)).unwrap();
+
+        assert_eq!(config_file.logfile, Some(String::from("/tmp/pacman.log")));
+        assert_eq!(config_file.output_format, Some(String::from("compact")));
+        assert_eq!(config_file.no_colors, Some(true));
+        assert_eq!(config_file.no_details, None);
+        assert_eq!(config_file.limit, Some(3));
+        assert_eq!(config_file.boot_packages, vec![String::from("grub")]);
+        assert_eq!(config_file.color, Some(String::from("always")));
+        assert_eq!(
+            config_file.colors.get("removed"),
+            Some(&String::from("light-red"))
+        );
+        assert_eq!(
+            config_file.hosts.get("web1"),
+            Some(&String::from("/srv/logs/web1/pacman.log"))
+        );
+        assert_eq!(
+            config_file.filters.get("kernel"),
+            Some(&vec![String::from("^linux"), String::from("-dkms$")])
+        );
+        assert_eq!(
+            config_file.queries.get("last-changes"),
+            Some(&Query {
+                last: Some(20),
+                actions: vec![String::from("upgraded"), String::from("downgraded")],
+                ..Default::default()
+            })
+        );
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_not_read_invalid_config_file() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "unknown = true").unwrap();
+
+        let result = ConfigFile::load(Some(&file_name));
+
+        assert!(result.is_err());
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_report_path_of_missing_config_file() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+
+        let error = ConfigFile::load(Some(&file_name)).unwrap_err();
+
+        match error.detail() {
+            ErrorDetail::UnreadableConfigFile { path, .. } => assert_eq!(path, &file_name),
+            detail => panic!("unexpected error {:?}", detail),
+        }
+        assert_eq!(error.exit_code(), 3)
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_config_file() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "limit = 3\nfirst = ").unwrap();
+
+        let result = ConfigFile::load(Some(&file_name));
+
+        match result.unwrap_err().detail() {
+            ErrorDetail::InvalidConfigFile { at, .. } => {
+                assert_eq!(at.path, file_name);
+                assert_eq!(at.line, Some(2))
+            }
+            detail => panic!("unexpected error {:?}", detail),
+        }
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_merge_config_files() {
+        let system = ConfigFile {
+            logfile: Some(String::from("/var/log/pacman.log")),
+            no_colors: Some(true),
+            ..Default::default()
+        };
+        let user = ConfigFile {
+            logfile: Some(String::from("/tmp/pacman.log")),
+            ..Default::default()
+        };
+
+        let merged = system.merge(user);
+
+        assert_eq!(merged.logfile, Some(String::from("/tmp/pacman.log")));
+        assert_eq!(merged.no_colors, Some(true))
+    }
+}
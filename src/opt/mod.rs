@@ -1,20 +1,35 @@
-use std::println;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
 use std::str::FromStr;
 
 use crate::error::Error;
 use crate::error::ErrorDetail;
 
 use chrono::NaiveDateTime;
+use clap::parser::ValueSource;
 use clap::ArgMatches;
 
-use regex::Regex;
+use regex::{Regex, RegexBuilder};
+use termion::color;
+
+use crate::opt::file::{ConfigFile, Query};
+use crate::pacman::action::Action;
+use crate::pacman::expression::Expression;
+use crate::pkghist::template::Template;
+use crate::source::{OnSkippedLine, Source};
 
 pub mod cli;
+pub mod file;
 
-pub fn parse_args<'a>(argv: &[String]) -> ArgMatches {
+pub fn parse_args(argv: &[String]) -> ArgMatches {
     cli::build_cli().get_matches_from(argv)
 }
 
+/// Like `parse_args`, but returns invalid arguments (and requests for help) instead of exiting.
+pub fn try_parse_args(argv: &[String]) -> Result<ArgMatches, clap::Error> {
+    cli::build_cli().try_get_matches_from(argv)
+}
+
 #[derive(Debug, PartialOrd, PartialEq)]
 pub enum Format {
     Plain {
@@ -24,9 +39,26 @@ pub enum Format {
     Json {
         without_details: bool,
     },
+    /// `columns` is empty to print the default columns
     Compact {
         with_colors: bool,
         without_details: bool,
+        columns: Vec<Column>,
+    },
+    Timeline {
+        with_colors: bool,
+    },
+    Markdown {
+        without_details: bool,
+    },
+    Html {
+        without_details: bool,
+    },
+    Template {
+        template: Template,
+    },
+    Log {
+        with_colors: bool,
     },
 }
 
@@ -48,6 +80,19 @@ impl FromStr for Format {
             Ok(Format::Compact {
                 with_colors: true,
                 without_details: false,
+                columns: Vec::new(),
+            })
+        } else if format_str == "timeline" {
+            Ok(Format::Timeline { with_colors: true })
+        } else if format_str == "log" {
+            Ok(Format::Log { with_colors: true })
+        } else if format_str == "markdown" {
+            Ok(Format::Markdown {
+                without_details: false,
+            })
+        } else if format_str == "html" {
+            Ok(Format::Html {
+                without_details: false,
             })
         } else {
             Err(Error::new(ErrorDetail::InvalidFormat))
@@ -55,6 +100,155 @@ impl FromStr for Format {
     }
 }
 
+/// A column of the compact output, chosen with `--columns`.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub enum Column {
+    Package,
+    Host,
+    Date,
+    Action,
+    Version,
+    From,
+    To,
+    Duration,
+}
+
+impl Column {
+    pub fn header(&self) -> &'static str {
+        match self {
+            Column::Package => "Package",
+            Column::Host => "Host",
+            Column::Date => "Date",
+            Column::Action => "Action",
+            Column::Version => "Version",
+            Column::From => "From",
+            Column::To => "To",
+            Column::Duration => "Duration",
+        }
+    }
+}
+
+impl FromStr for Column {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "package" => Ok(Column::Package),
+            "host" => Ok(Column::Host),
+            "date" => Ok(Column::Date),
+            "action" => Ok(Column::Action),
+            "version" => Ok(Column::Version),
+            "from" => Ok(Column::From),
+            "to" => Ok(Column::To),
+            "duration" => Ok(Column::Duration),
+            _ => Err(Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Unknown column '{}'", s),
+            })),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(ColorMode::Auto),
+            "always" => Ok(ColorMode::Always),
+            "never" => Ok(ColorMode::Never),
+            _ => Err(Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Unknown color mode '{}', use auto, always or never", s),
+            })),
+        }
+    }
+}
+
+const COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+/// The colors of the actions set in the configuration file.
+///
+/// Actions without a color keep the colors of the output format.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Palette {
+    colors: HashMap<Action, u8>,
+}
+
+impl Palette {
+    pub fn from_config(colors: &BTreeMap<String, String>) -> Result<Palette, Error> {
+        let mut palette = Palette::default();
+        for (action, color) in colors {
+            let action: Action = action.parse().map_err(|_| {
+                Error::new(ErrorDetail::InvalidConfig {
+                    msg: format!("Unknown action '{}' in colors", action),
+                })
+            })?;
+            palette.colors.insert(action, parse_color(color)?);
+        }
+        Ok(palette)
+    }
+
+    pub fn color(&self, action: &Action) -> Option<color::AnsiValue> {
+        self.colors.get(action).map(|c| color::AnsiValue(*c))
+    }
+}
+
+/*
+ * A name like red or light-red, or an ANSI color number from 0 to 255
+ */
+fn parse_color(color: &str) -> Result<u8, Error> {
+    let name = color.to_lowercase();
+    let (base, light) = match name.strip_prefix("light-") {
+        Some(base) => (base, 8),
+        None => (name.as_str(), 0),
+    };
+    match COLOR_NAMES.iter().position(|c| *c == base) {
+        Some(idx) => Ok(idx as u8 + light),
+        None => color.parse().map_err(|_| {
+            Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Unknown color '{}'", color),
+            })
+        }),
+    }
+}
+
+impl Format {
+    /// Turns the colors off, e.g. if stdout is no terminal.
+    pub fn disable_colors(&mut self) {
+        match self {
+            Format::Plain { with_colors, .. }
+            | Format::Compact { with_colors, .. }
+            | Format::Timeline { with_colors }
+            | Format::Log { with_colors } => *with_colors = false,
+            Format::Json { .. }
+            | Format::Markdown { .. }
+            | Format::Html { .. }
+            | Format::Template { .. } => {}
+        }
+    }
+
+    pub fn with_colors(&self) -> bool {
+        match *self {
+            Format::Plain { with_colors, .. }
+            | Format::Compact { with_colors, .. }
+            | Format::Timeline { with_colors }
+            | Format::Log { with_colors } => with_colors,
+            Format::Json { .. }
+            | Format::Markdown { .. }
+            | Format::Html { .. }
+            | Format::Template { .. } => false,
+        }
+    }
+}
+
 #[derive(Debug, PartialOrd, PartialEq)]
 pub enum Direction {
     Forwards { n: usize },
@@ -62,35 +256,102 @@ pub enum Direction {
 }
 
 impl Direction {
-    fn from_first(n: u32) -> Direction {
+    pub(crate) fn from_first(n: u32) -> Direction {
         Direction::Forwards { n: n as usize }
     }
 
-    fn from_last(n: u32) -> Direction {
+    pub(crate) fn from_last(n: u32) -> Direction {
         Direction::Backwards { n: n as usize }
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum Mode {
+    History,
+    /// `churn_window` is the number of days between flip-flops of a churning package
+    Audit {
+        churn_window: u32,
+    },
+    Tui,
+    Activity,
+    Fleet,
+    Advisories {
+        file: String,
+    },
+    /// `packages` are tracked in addition to the default boot-critical packages
+    Kernel {
+        packages: Vec<String>,
+    },
+    Reboot,
+}
+
+/// Another machine's logfile, given as `--host name=path`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Host {
+    pub name: String,
+    pub logfile: String,
+}
+
+impl FromStr for Host {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((name, logfile)) if !name.is_empty() && !logfile.is_empty() => Ok(Host {
+                name: String::from(name),
+                logfile: String::from(logfile),
+            }),
+            _ => Err(Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Invalid host '{}' [Format: name=path]", s),
+            })),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Config {
+    pub mode: Mode,
     pub exclude: bool,
     pub removed_only: bool,
     pub with_removed: bool,
     pub logfile: String,
+    /// Replaces `logfile` if not empty
+    pub hosts: Vec<Host>,
+    pub source: Source,
     pub filters: Vec<Regex>,
+    /// How a search narrowing the filters is matched
+    pub matching: Matching,
+    pub ignore_case: bool,
+    /// Narrows the packages matching the filters further
+    pub search: Option<Regex>,
     pub format: Format,
     pub limit: Option<u32>,
     pub direction: Option<Direction>,
     pub after: Option<NaiveDateTime>,
+    pub before: Option<NaiveDateTime>,
+    pub actions: Vec<Action>,
+    /// Only events matching the expression are considered
+    pub expression: Option<Expression>,
+    pub durations: bool,
+    /// The boots exported by `journalctl --list-boots -o json`
+    pub boots: Option<String>,
+    /// With `ColorMode::Auto` the colors of the format are turned off if stdout is no terminal
+    pub color: ColorMode,
+    pub palette: Palette,
+    /// Ignores the skipped lines by default
+    pub on_skipped_line: OnSkippedLine,
 }
 
 impl Default for Config {
     fn default() -> Config {
         Config {
+            mode: Mode::History,
             exclude: false,
             removed_only: false,
             with_removed: false,
             logfile: String::from("/var/log/pacman.log"),
+            hosts: Vec::new(),
+            source: Source::Pacman,
             format: Format::Plain {
                 with_colors: true,
                 without_details: false,
@@ -98,7 +359,18 @@ impl Default for Config {
             limit: None,
             direction: None,
             after: None,
+            before: None,
+            actions: Vec::new(),
+            expression: None,
+            durations: false,
+            boots: None,
+            color: ColorMode::Auto,
+            palette: Palette::default(),
             filters: Vec::new(),
+            matching: Matching::Regex,
+            ignore_case: false,
+            search: None,
+            on_skipped_line: |_| {},
         }
     }
 }
@@ -108,42 +380,150 @@ impl Config {
         Default::default()
     }
 
-    pub fn from_arg_matches(matches: &ArgMatches) -> Config {
-        let filters = match matches.get_many::<String>("filter") {
-            Some(filters) => filters.fold(Vec::new(), |mut current, f| {
-                println!("{}", f);
-                let r = Regex::new(f).unwrap();
-                current.push(r);
-                current
-            }),
-            None => Vec::new(),
-        };
+    /// The logfiles to read, tagged with their host's name unless only `logfile` is read.
+    pub fn logfiles(&self) -> Vec<(Option<&str>, &str)> {
+        if self.hosts.is_empty() {
+            vec![(None, self.logfile.as_str())]
+        } else {
+            self.hosts
+                .iter()
+                .map(|host| (Some(host.name.as_str()), host.logfile.as_str()))
+                .collect()
+        }
+    }
+
+    /// Compiles a search the same way as the filters.
+    pub fn parse_search(&self, search: &str) -> Result<Regex, Error> {
+        parse_filter(search, self.matching, self.ignore_case, "search")
+    }
 
-        let with_colors = !matches.get_flag("no-colors");
+    pub fn from_arg_matches(matches: &ArgMatches, file: &ConfigFile) -> Result<Config, Error> {
+        let default_query = Query::default();
+        let query = match matches.subcommand() {
+            Some(("run", run_matches)) => {
+                let name = run_matches.get_one::<String>("query").unwrap();
+                file.queries.get(name).ok_or_else(|| {
+                    Error::new(ErrorDetail::InvalidConfig {
+                        msg: format!("Unknown query '{}'", name),
+                    })
+                })?
+            }
+            _ => &default_query,
+        };
 
-        let without_details = matches.get_flag("no-details");
+        let matching = if matches.get_flag("exact") {
+            Matching::Exact
+        } else if matches.get_flag("glob") {
+            Matching::Glob
+        } else {
+            Matching::Regex
+        };
+        let ignore_case = matches.get_flag("ignore-case");
 
-        let format = match matches
-            .get_one::<String>("output-format")
-            .unwrap()
-            .parse()
-            .unwrap()
+        let mut filters = Vec::new();
+        let subcommand_filters = matches
+            .subcommand()
+            .and_then(|(_, m)| m.try_get_many::<String>("filter").ok().flatten());
+        for f in matches
+            .get_many::<String>("filter")
+            .into_iter()
+            .flatten()
+            .chain(subcommand_filters.into_iter().flatten())
         {
-            Format::Plain { .. } => Format::Plain {
-                with_colors,
-                without_details,
+            filters.push(parse_filter(f, matching, ignore_case, "arguments")?)
+        }
+
+        if let Some(filter_file) = matches.get_one::<String>("filter-file") {
+            for f in read_filter_file(filter_file)? {
+                filters.push(parse_filter(
+                    &f,
+                    matching,
+                    ignore_case,
+                    &format!("filter file '{}'", filter_file),
+                )?)
+            }
+        }
+
+        for f in &query.filters {
+            filters.push(parse_filter(f, matching, ignore_case, "query")?)
+        }
+
+        if let Some(filter_sets) = matches.get_many::<String>("filter-set") {
+            for name in filter_sets {
+                let filter_set = file.filters.get(name).ok_or_else(|| {
+                    Error::new(ErrorDetail::InvalidConfig {
+                        msg: format!("Unknown filter set '{}'", name),
+                    })
+                })?;
+                for f in filter_set {
+                    filters.push(parse_filter(
+                        f,
+                        matching,
+                        ignore_case,
+                        &format!("filter set '{}'", name),
+                    )?)
+                }
+            }
+        }
+
+        let color = match matches.get_one::<String>("color") {
+            Some(color) => color.parse()?,
+            None if matches.get_flag("no-colors") => ColorMode::Never,
+            None => match (&file.color, file.no_colors) {
+                (Some(color), _) => color.parse()?,
+                (None, Some(true)) => ColorMode::Never,
+                (None, _) => ColorMode::Auto,
+            },
+        };
+        let with_colors = color != ColorMode::Never;
+        let palette = Palette::from_config(&file.colors)?;
+
+        let without_details =
+            if from_command_line(matches, "no-details") || from_command_line(matches, "details") {
+                matches.get_flag("no-details")
+            } else {
+                file.no_details.unwrap_or(false)
+            };
+
+        let output_format = match query.output_format.as_ref().or(file.output_format.as_ref()) {
+            Some(output_format) if !from_command_line(matches, "output-format") => output_format,
+            _ => matches.get_one::<String>("output-format").unwrap(),
+        };
+
+        let columns = match matches.get_many::<String>("columns") {
+            Some(columns) => columns
+                .map(|c| c.parse())
+                .collect::<Result<Vec<Column>, Error>>()?,
+            None => Vec::new(),
+        };
+
+        let format = match matches.get_one::<String>("format-template") {
+            Some(template) => Format::Template {
+                template: template.parse()?,
             },
-            Format::Compact { .. } => Format::Compact {
-                with_colors,
-                without_details,
+            None => match output_format.parse()? {
+                Format::Plain { .. } => Format::Plain {
+                    with_colors,
+                    without_details,
+                },
+                Format::Compact { .. } => Format::Compact {
+                    with_colors,
+                    without_details,
+                    columns: columns.clone(),
+                },
+                Format::Json { .. } => Format::Json { without_details },
+                Format::Timeline { .. } => Format::Timeline { with_colors },
+                Format::Log { .. } => Format::Log { with_colors },
+                Format::Markdown { .. } => Format::Markdown { without_details },
+                Format::Html { .. } => Format::Html { without_details },
+                format => format,
             },
-            Format::Json { .. } => Format::Json { without_details },
         };
 
         let limit = match matches.get_one::<String>("limit") {
             Some(all) if all == "all" => None,
             Some(v) => Some(v.parse::<u32>().unwrap()),
-            None => None,
+            None => query.limit.or(file.limit),
         };
 
         let direction = if matches.contains_id("first") {
@@ -154,35 +534,247 @@ impl Config {
             Some(Direction::from_last(
                 matches.get_one::<String>("last").unwrap().parse().unwrap(),
             ))
+        } else if let Some(first) = query.first {
+            Some(Direction::from_first(first))
         } else {
-            None
+            query.last.map(Direction::from_last)
         };
 
         let after = match matches.get_one::<String>("after") {
             Some(date_str) => {
                 Some(NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M").unwrap())
             }
-            None => None,
+            None => query.after.as_ref().map(|a| parse_date(a)).transpose()?,
         };
 
-        Config {
-            exclude: matches.get_flag("exclude"),
-            removed_only: matches.get_flag("removed-only"),
-            with_removed: matches.get_flag("with-removed"),
-            logfile: matches.get_one::<String>("logfile").unwrap().to_owned(),
+        let before = match matches.get_one::<String>("before") {
+            Some(date_str) => {
+                Some(NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M").unwrap())
+            }
+            None => query.before.as_ref().map(|b| parse_date(b)).transpose()?,
+        };
+
+        let actions = match matches.get_many::<String>("action") {
+            Some(actions) => actions
+                .map(|a| a.parse())
+                .collect::<Result<Vec<Action>, Error>>()?,
+            None => query
+                .actions
+                .iter()
+                .map(|a| a.parse())
+                .collect::<Result<Vec<Action>, Error>>()?,
+        };
+
+        let expression = match matches.get_one::<String>("expression") {
+            Some(expression) => Some(expression.parse()?),
+            None => query.expression.as_ref().map(|e| e.parse()).transpose()?,
+        };
+
+        let mode = match matches.subcommand() {
+            Some(("audit", audit_matches)) => Mode::Audit {
+                churn_window: audit_matches
+                    .get_one::<String>("churn-window")
+                    .map_or(7, |w| w.parse().unwrap()),
+            },
+            Some(("tui", _)) => Mode::Tui,
+            Some(("activity", _)) => Mode::Activity,
+            Some(("fleet", _)) => Mode::Fleet,
+            Some(("reboot", _)) => Mode::Reboot,
+            Some(("advisories", advisories_matches)) => Mode::Advisories {
+                file: advisories_matches
+                    .get_one::<String>("file")
+                    .unwrap()
+                    .to_owned(),
+            },
+            Some(("kernel", kernel_matches)) => Mode::Kernel {
+                packages: file
+                    .boot_packages
+                    .iter()
+                    .chain(
+                        kernel_matches
+                            .get_many::<String>("package")
+                            .unwrap_or_default(),
+                    )
+                    .cloned()
+                    .collect(),
+            },
+            _ => Mode::History,
+        };
+
+        let exclude =
+            if from_command_line(matches, "exclude") || from_command_line(matches, "include") {
+                matches.get_flag("exclude")
+            } else {
+                query.exclude.unwrap_or(false)
+            };
+
+        let (removed_only, with_removed) = if from_command_line(matches, "removed-only")
+            || from_command_line(matches, "with-removed")
+        {
+            (
+                matches.get_flag("removed-only"),
+                matches.get_flag("with-removed"),
+            )
+        } else {
+            (
+                query.removed_only.or(file.removed_only).unwrap_or(false),
+                query.with_removed.or(file.with_removed).unwrap_or(false),
+            )
+        };
+
+        let source = match matches.get_one::<String>("source").or(file.source.as_ref()) {
+            Some(source) => source.parse()?,
+            None => Source::Pacman,
+        };
+
+        let logfile = match matches
+            .get_one::<String>("logfile")
+            .or(file.logfile.as_ref())
+        {
+            Some(logfile) => logfile,
+            None => source.log_source().default_logfile(),
+        };
+
+        let hosts = match matches.get_many::<String>("host") {
+            Some(hosts) => hosts
+                .map(|h| h.parse())
+                .collect::<Result<Vec<Host>, Error>>()?,
+            None if from_command_line(matches, "logfile") => Vec::new(),
+            None => file
+                .hosts
+                .iter()
+                .map(|(name, logfile)| Host {
+                    name: name.clone(),
+                    logfile: logfile.clone(),
+                })
+                .collect(),
+        };
+
+        Ok(Config {
+            mode,
+            exclude,
+            removed_only,
+            with_removed,
+            logfile: logfile.to_owned(),
+            hosts,
+            source,
             limit,
             filters,
+            matching,
+            ignore_case,
             format,
             direction,
             after,
+            before,
+            actions,
+            expression,
+            durations: matches.get_flag("durations") || columns.contains(&Column::Duration),
+            boots: matches.get_one::<String>("boots").cloned(),
+            color,
+            palette,
+            ..Default::default()
+        })
+    }
+}
+
+/// How the filters are matched against the package names, wherever they are given.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Matching {
+    Regex,
+    Exact,
+    Glob,
+}
+
+fn parse_filter(
+    filter: &str,
+    matching: Matching,
+    ignore_case: bool,
+    origin: &str,
+) -> Result<Regex, Error> {
+    let pattern = match matching {
+        Matching::Regex => String::from(filter),
+        Matching::Exact => format!("^{}$", regex::escape(filter)),
+        Matching::Glob => glob_to_regex(filter),
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| {
+            Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Invalid filter '{}' in {}: {}", filter, origin, e),
+            })
+        })
+}
+
+/*
+ * `*` matches any number of characters, `?` a single one and `[...]` (or `[!...]`) a class,
+ * everything else is matched literally. The glob has to match the whole package name.
+ */
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    let mut in_class = false;
+    for c in glob.chars() {
+        match c {
+            '[' if !in_class => {
+                in_class = true;
+                regex.push('[')
+            }
+            '!' if in_class && regex.ends_with('[') => regex.push('^'),
+            ']' if in_class && !regex.ends_with('[') && !regex.ends_with("[^") => {
+                in_class = false;
+                regex.push(']')
+            }
+            '\\' | '[' | '&' | '~' if in_class => {
+                regex.push('\\');
+                regex.push(c)
+            }
+            c if in_class => regex.push(c),
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
         }
     }
+    regex.push('$');
+    regex
+}
+
+/// One filter per line, empty lines and lines starting with `#` are skipped.
+fn read_filter_file(file: &str) -> Result<Vec<String>, Error> {
+    let content = fs::read_to_string(file).map_err(|e| {
+        Error::new(ErrorDetail::UnreadableFilterFile {
+            path: String::from(file),
+            msg: e.to_string(),
+        })
+    })?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(String::from)
+        .collect())
+}
+
+fn parse_date(date_str: &str) -> Result<NaiveDateTime, Error> {
+    NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M").map_err(|_| {
+        Error::new(ErrorDetail::InvalidConfig {
+            msg: format!("Invalid date '{}' [Format: \"YYYY-MM-DD HH:MM\"]", date_str),
+        })
+    })
+}
+
+fn from_command_line(matches: &ArgMatches, id: &str) -> bool {
+    matches.value_source(id) == Some(ValueSource::CommandLine)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
+    use std::fs::File;
+    use std::io::Write;
     use std::println;
 
+    use filepath::FilePath;
+
     use super::*;
     use chrono::{NaiveDate, NaiveTime};
 
@@ -244,7 +836,8 @@ mod tests {
             format.unwrap(),
             Format::Compact {
                 with_colors: true,
-                without_details: false
+                without_details: false,
+                columns: Vec::new()
             }
         )
     }
@@ -257,6 +850,37 @@ mod tests {
             format.unwrap(),
             Format::Compact {
                 with_colors: true,
+                without_details: false,
+                columns: Vec::new()
+            }
+        )
+    }
+
+    #[test]
+    fn should_parse_format_timeline() {
+        let format: Result<Format, Error> = "timeline".parse();
+        assert_eq!(format.unwrap(), Format::Timeline { with_colors: true });
+        let format: Result<Format, Error> = "log".parse();
+        assert_eq!(format.unwrap(), Format::Log { with_colors: true })
+    }
+
+    #[test]
+    fn should_parse_format_markdown() {
+        let format: Result<Format, Error> = "markdown".parse();
+        assert_eq!(
+            format.unwrap(),
+            Format::Markdown {
+                without_details: false
+            }
+        )
+    }
+
+    #[test]
+    fn should_parse_format_html() {
+        let format: Result<Format, Error> = "HTML".parse();
+        assert_eq!(
+            format.unwrap(),
+            Format::Html {
                 without_details: false
             }
         )
@@ -275,7 +899,7 @@ mod tests {
     #[test]
     fn should_create_config_from_args() {
         let matches = parse_args(&[String::from("pkghist")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.logfile, "/var/log/pacman.log");
         assert_eq!(config.filters.is_empty(), true);
         assert_eq!(config.exclude, false);
@@ -297,7 +921,7 @@ mod tests {
             String::from("--exclude"),
             String::from("^lib"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.filters.is_empty(), false);
         assert_eq!(config.exclude, true)
     }
@@ -305,7 +929,7 @@ mod tests {
     #[test]
     fn should_create_config_from_args_no_colors() {
         let matches = parse_args(&[String::from("pkghist"), String::from("--no-colors")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         println!("{:?}", config);
         assert_eq!(config.logfile, "/var/log/pacman.log");
         assert_eq!(config.filters.is_empty(), true);
@@ -323,7 +947,7 @@ mod tests {
     #[test]
     fn should_create_config_from_args_removed_only() {
         let matches = parse_args(&[String::from("pkghist"), String::from("--removed-only")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.logfile, "/var/log/pacman.log");
         assert_eq!(config.filters.is_empty(), true);
         assert_eq!(config.with_removed, false);
@@ -333,7 +957,7 @@ mod tests {
     #[test]
     fn should_create_config_from_args_with_removed() {
         let matches = parse_args(&[String::from("pkghist"), String::from("--with-removed")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.filters.is_empty(), true);
         assert_eq!(config.with_removed, true);
         assert_eq!(config.removed_only, false)
@@ -342,11 +966,118 @@ mod tests {
     #[test]
     fn should_create_config_from_args_filters() {
         let matches = parse_args(&[String::from("pkghist"), String::from("linux")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.filters.is_empty(), false);
         assert_eq!(config.filters.len(), 1);
     }
 
+    #[test]
+    fn should_not_create_config_from_args_invalid_filter() {
+        let matches = parse_args(&[String::from("pkghist"), String::from("gtk+(")]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        assert_eq!(config.unwrap_err().exit_code(), 3)
+    }
+
+    #[test]
+    fn should_create_config_from_args_exact() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--exact"),
+            String::from("gtk+"),
+            String::from("python-foo"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert!(config.filters[0].is_match("gtk+"));
+        assert!(!config.filters[0].is_match("gtkk"));
+        assert!(config.filters[1].is_match("python-foo"));
+        assert!(!config.filters[1].is_match("python-foo-bar"))
+    }
+
+    #[test]
+    fn should_create_config_from_args_glob() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--glob"),
+            String::from("python-*"),
+            String::from("lib?"),
+            String::from("[!a-c]*++"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert!(config.filters[0].is_match("python-foo"));
+        assert!(!config.filters[0].is_match("mypython-foo"));
+        assert!(config.filters[1].is_match("libx"));
+        assert!(!config.filters[1].is_match("libxy"));
+        assert!(config.filters[2].is_match("gcc++"));
+        assert!(!config.filters[2].is_match("c++"))
+    }
+
+    #[test]
+    fn should_translate_globs() {
+        assert_eq!(glob_to_regex("python-*"), "^python\\-.*$");
+        assert_eq!(glob_to_regex("c++"), "^c\\+\\+$");
+        assert_eq!(glob_to_regex("[!]a]?"), "^[^]a].$")
+    }
+
+    #[test]
+    fn should_not_create_config_from_args_exact_and_glob() {
+        let matches = try_parse_args(&[
+            String::from("pkghist"),
+            String::from("--exact"),
+            String::from("--glob"),
+            String::from("linux"),
+        ]);
+        assert!(matches.is_err())
+    }
+
+    #[test]
+    fn should_create_config_from_args_ignore_case() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("-i"),
+            String::from("--exact"),
+            String::from("PyQt5"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert!(config.filters[0].is_match("pyqt5"));
+        assert!(config.filters[0].is_match("PYQT5"))
+    }
+
+    #[test]
+    fn should_create_config_from_filter_file() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "# packages of the web servers\ngtk+\n\n  c++  \n").unwrap();
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--exact"),
+            String::from("--filter-file"),
+            file_name.clone(),
+            String::from("vim"),
+        ]);
+
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+
+        let filters: Vec<&str> = config.filters.iter().map(|f| f.as_str()).collect();
+        assert_eq!(filters, vec!["^vim$", "^gtk\\+$", "^c\\+\\+$"]);
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_not_create_config_from_missing_filter_file() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--filter-file"),
+            uuid::Uuid::new_v4().to_string(),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        let error = config.unwrap_err();
+        match error.detail() {
+            ErrorDetail::UnreadableFilterFile { .. } => {}
+            detail => panic!("unexpected error {:?}", detail),
+        }
+        assert_eq!(error.exit_code(), 3)
+    }
+
     #[test]
     fn should_create_config_from_args_format_json() {
         let matches = parse_args(&[
@@ -354,7 +1085,7 @@ mod tests {
             String::from("--output-format"),
             String::from("json"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(
             config.format,
             Format::Json {
@@ -371,7 +1102,7 @@ mod tests {
             String::from("json"),
             String::from("--no-details"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(
             config.format,
             Format::Json {
@@ -387,12 +1118,13 @@ mod tests {
             String::from("--output-format"),
             String::from("compact"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(
             config.format,
             Format::Compact {
                 with_colors: true,
-                without_details: false
+                without_details: false,
+                columns: Vec::new()
             }
         )
     }
@@ -405,12 +1137,13 @@ mod tests {
             String::from("compact"),
             String::from("--no-details"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(
             config.format,
             Format::Compact {
                 with_colors: true,
-                without_details: true
+                without_details: true,
+                columns: Vec::new()
             }
         )
     }
@@ -422,14 +1155,14 @@ mod tests {
             String::from("--limit"),
             String::from("3"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.limit, Some(3))
     }
 
     #[test]
     fn should_create_config_from_args_limit_none() {
         let matches = parse_args(&[String::from("pkghist")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.limit, None)
     }
 
@@ -440,14 +1173,14 @@ mod tests {
             String::from("--first"),
             String::from("50"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.direction, Some(Direction::Forwards { n: 50 }))
     }
 
     #[test]
     fn should_create_config_from_args_first_none() {
         let matches = parse_args(&[String::from("pkghist")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.direction, None)
     }
 
@@ -458,7 +1191,7 @@ mod tests {
             String::from("--last"),
             String::from("50"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.direction, Some(Direction::Backwards { n: 50 }))
     }
 
@@ -469,20 +1202,626 @@ mod tests {
             String::from("--after"),
             String::from("2019-01-01 12:00"),
         ]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(
+            config.after,
+            Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+                NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            ))
+        )
+    }
+
+    #[test]
+    fn should_create_config_from_args_audit() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("audit"),
+            String::from("--logfile"),
+            String::from("/tmp/pacman.log"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Audit { churn_window: 7 });
+        assert_eq!(config.logfile, "/tmp/pacman.log")
+    }
+
+    #[test]
+    fn should_create_config_from_args_audit_filters() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--exclude"),
+            String::from("audit"),
+            String::from("^linux"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Audit { churn_window: 7 });
+        assert_eq!(config.filters.len(), 1);
+        assert!(config.exclude)
+    }
+
+    #[test]
+    fn should_create_config_from_args_audit_exact() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("audit"),
+            String::from("--exact"),
+            String::from("gtk+"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        let filters: Vec<&str> = config.filters.iter().map(|f| f.as_str()).collect();
+        assert_eq!(filters, vec!["^gtk\\+$"])
+    }
+
+    #[test]
+    fn should_create_config_from_args_run_glob() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("run"),
+            String::from("python"),
+            String::from("--glob"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.queries.insert(
+            String::from("python"),
+            Query {
+                filters: vec![String::from("python-*")],
+                ..Default::default()
+            },
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        let filters: Vec<&str> = config.filters.iter().map(|f| f.as_str()).collect();
+        assert_eq!(filters, vec!["^python\\-.*$"])
+    }
+
+    #[test]
+    fn should_create_config_from_args_audit_churn_window() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("audit"),
+            String::from("--churn-window"),
+            String::from("30"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Audit { churn_window: 30 })
+    }
+
+    #[test]
+    fn should_create_config_from_args_tui() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("tui"),
+            String::from("--with-removed"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Tui);
+        assert!(config.with_removed)
+    }
+
+    #[test]
+    fn should_create_config_from_config_file() {
+        let matches = parse_args(&[String::from("pkghist")]);
+        let file = ConfigFile {
+            logfile: Some(String::from("/tmp/pacman.log")),
+            output_format: Some(String::from("compact")),
+            no_colors: Some(true),
+            limit: Some(5),
+            with_removed: Some(true),
+            ..Default::default()
+        };
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.logfile, "/tmp/pacman.log");
+        assert_eq!(config.limit, Some(5));
+        assert!(config.with_removed);
+        assert_eq!(
+            config.format,
+            Format::Compact {
+                with_colors: false,
+                without_details: false,
+                columns: Vec::new()
+            }
+        )
+    }
+
+    #[test]
+    fn should_override_config_file_from_args() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("-o"),
+            String::from("json"),
+            String::from("--logfile"),
+            String::from("/var/log/pacman.log"),
+            String::from("--removed-only"),
+        ]);
+        let file = ConfigFile {
+            logfile: Some(String::from("/tmp/pacman.log")),
+            output_format: Some(String::from("compact")),
+            with_removed: Some(true),
+            ..Default::default()
+        };
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.logfile, "/var/log/pacman.log");
+        assert!(config.removed_only);
+        assert!(!config.with_removed);
+        assert_eq!(
+            config.format,
+            Format::Json {
+                without_details: false
+            }
+        )
+    }
+
+    #[test]
+    fn should_override_config_file_no_details_from_args() {
+        let file = ConfigFile {
+            no_details: Some(true),
+            ..Default::default()
+        };
+        let plain = |without_details: bool| Format::Plain {
+            with_colors: true,
+            without_details,
+        };
+        let matches = parse_args(&[String::from("pkghist")]);
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.format, plain(true));
+
+        let matches = parse_args(&[String::from("pkghist"), String::from("--details")]);
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.format, plain(false));
+
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--details"),
+            String::from("--no-details"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.format, plain(true))
+    }
+
+    #[test]
+    fn should_create_config_from_filter_set() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--filter-set"),
+            String::from("kernel"),
+            String::from("vim"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.filters.insert(
+            String::from("kernel"),
+            vec![String::from("^linux"), String::from("-dkms$")],
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.filters.len(), 3)
+    }
+
+    #[test]
+    fn should_match_filter_set_and_query_filters_like_arguments() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--glob"),
+            String::from("-i"),
+            String::from("--filter-set"),
+            String::from("python"),
+            String::from("run"),
+            String::from("qt"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.filters
+            .insert(String::from("python"), vec![String::from("python-*")]);
+        file.queries.insert(
+            String::from("qt"),
+            Query {
+                filters: vec![String::from("PyQt?")],
+                ..Default::default()
+            },
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        let filters: Vec<&str> = config.filters.iter().map(|f| f.as_str()).collect();
+        assert_eq!(filters, vec!["^PyQt.$", "^python\\-.*$"]);
+        assert!(config.filters[0].is_match("pyqt5"))
+    }
+
+    #[test]
+    fn should_not_create_config_from_unknown_filter_set() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--filter-set"),
+            String::from("kernel"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        assert!(config.is_err())
+    }
+
+    #[test]
+    fn should_create_config_from_query() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("run"),
+            String::from("kernel-audit"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.queries.insert(
+            String::from("kernel-audit"),
+            Query {
+                filters: vec![String::from("^linux$"), String::from("^nvidia")],
+                after: Some(String::from("2019-01-01 12:00")),
+                actions: vec![String::from("upgraded")],
+                output_format: Some(String::from("compact")),
+                last: Some(20),
+                ..Default::default()
+            },
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.mode, Mode::History);
+        assert_eq!(config.filters.len(), 2);
+        assert_eq!(config.actions, vec![Action::Upgraded]);
+        assert_eq!(config.direction, Some(Direction::Backwards { n: 20 }));
         assert_eq!(
             config.after,
             Some(NaiveDateTime::new(
                 NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
                 NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
             ))
+        );
+        assert_eq!(
+            config.format,
+            Format::Compact {
+                with_colors: true,
+                without_details: false,
+                columns: Vec::new()
+            }
+        )
+    }
+
+    #[test]
+    fn should_create_config_from_args_expression() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--expression"),
+            String::from("name~'^python' and not name~'-git$'"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        let expression = config.expression.unwrap();
+        let event = |package: &str| {
+            format!("[2024-01-02 10:00] [ALPM] installed {} (1.0-1)", package)
+                .parse()
+                .unwrap()
+        };
+        assert!(expression.matches(&event("python-requests")));
+        assert!(!expression.matches(&event("python-foo-git")));
+        assert!(!expression.matches(&event("vim")))
+    }
+
+    #[test]
+    fn should_not_create_config_from_args_invalid_expression() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("-e"),
+            String::from("name~'^python' and"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        assert_eq!(config.unwrap_err().exit_code(), 3)
+    }
+
+    #[test]
+    fn should_create_config_from_query_expression() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("run"),
+            String::from("upgrades"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.queries.insert(
+            String::from("upgrades"),
+            Query {
+                expression: Some(String::from("action=upgraded")),
+                ..Default::default()
+            },
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert!(config.expression.is_some())
+    }
+
+    #[test]
+    fn should_override_query_from_args() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("run"),
+            String::from("changes"),
+            String::from("--action"),
+            String::from("removed"),
+            String::from("-o"),
+            String::from("json"),
+        ]);
+        let mut file = ConfigFile::default();
+        file.queries.insert(
+            String::from("changes"),
+            Query {
+                actions: vec![String::from("upgraded")],
+                output_format: Some(String::from("compact")),
+                ..Default::default()
+            },
+        );
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.actions, vec![Action::Removed]);
+        assert_eq!(
+            config.format,
+            Format::Json {
+                without_details: false
+            }
+        )
+    }
+
+    #[test]
+    fn should_override_query_exclude_from_args() {
+        let mut file = ConfigFile::default();
+        file.queries.insert(
+            String::from("no-libs"),
+            Query {
+                filters: vec![String::from("^lib")],
+                exclude: Some(true),
+                ..Default::default()
+            },
+        );
+        let run = |args: &[&str]| {
+            let matches = parse_args(&args.iter().map(|a| String::from(*a)).collect::<Vec<_>>());
+            Config::from_arg_matches(&matches, &file).unwrap()
+        };
+        assert!(run(&["pkghist", "run", "no-libs"]).exclude);
+        assert!(!run(&["pkghist", "--include", "run", "no-libs"]).exclude);
+        assert!(run(&["pkghist", "--include", "--exclude", "run", "no-libs"]).exclude)
+    }
+
+    #[test]
+    fn should_not_create_config_from_unknown_query() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("run"),
+            String::from("kernel-audit"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        assert!(config.is_err())
+    }
+
+    #[test]
+    fn should_create_config_from_args_before() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--before"),
+            String::from("2019-01-01 12:00"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(
+            config.before,
+            Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(),
+                NaiveTime::from_hms_opt(12, 0, 0).unwrap(),
+            ))
         )
     }
 
     #[test]
     fn should_create_config_from_args_after_none() {
         let matches = parse_args(&[String::from("pkghist")]);
-        let config = Config::from_arg_matches(&matches);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
         assert_eq!(config.after, None)
     }
+
+    #[test]
+    fn should_create_config_from_args_format_template() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--format-template"),
+            String::from("{date} {package}"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(
+            config.format,
+            Format::Template {
+                template: "{date} {package}".parse().unwrap()
+            }
+        )
+    }
+
+    #[test]
+    fn should_not_create_config_from_args_invalid_format_template() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--format-template"),
+            String::from("{#date}"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default());
+        assert!(config.is_err())
+    }
+
+    #[test]
+    fn should_create_config_from_args_source() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--source"),
+            String::from("apt"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.source, Source::Apt);
+        assert_eq!(config.logfile, "/var/log/apt/history.log")
+    }
+
+    #[test]
+    fn should_create_config_from_config_file_source() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--logfile"),
+            String::from("/tmp/dpkg.log"),
+        ]);
+        let file = ConfigFile {
+            source: Some(String::from("dpkg")),
+            ..Default::default()
+        };
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.source, Source::Dpkg);
+        assert_eq!(config.logfile, "/tmp/dpkg.log")
+    }
+
+    #[test]
+    fn should_create_config_from_args_hosts() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("fleet"),
+            String::from("--host"),
+            String::from("web1=/srv/logs/web1/pacman.log"),
+            String::from("--host"),
+            String::from("web2=/srv/logs/web2/pacman.log"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Fleet);
+        assert_eq!(
+            config.logfiles(),
+            vec![
+                (Some("web1"), "/srv/logs/web1/pacman.log"),
+                (Some("web2"), "/srv/logs/web2/pacman.log")
+            ]
+        )
+    }
+
+    #[test]
+    fn should_create_config_from_config_file_hosts() {
+        let mut file = ConfigFile::default();
+        file.hosts.insert(
+            String::from("web1"),
+            String::from("/srv/logs/web1/pacman.log"),
+        );
+
+        let matches = parse_args(&[String::from("pkghist")]);
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(
+            config.hosts,
+            vec![Host {
+                name: String::from("web1"),
+                logfile: String::from("/srv/logs/web1/pacman.log")
+            }]
+        );
+
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--logfile"),
+            String::from("/tmp/pacman.log"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.logfiles(), vec![(None, "/tmp/pacman.log")])
+    }
+
+    #[test]
+    fn should_not_parse_invalid_host() {
+        assert!("web1".parse::<Host>().is_err());
+        assert!("web1=".parse::<Host>().is_err())
+    }
+
+    #[test]
+    fn should_create_config_from_args_advisories() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("advisories"),
+            String::from("/tmp/all.json"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(
+            config.mode,
+            Mode::Advisories {
+                file: String::from("/tmp/all.json")
+            }
+        )
+    }
+
+    #[test]
+    fn should_create_config_from_args_kernel() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("kernel"),
+            String::from("--package"),
+            String::from("efibootmgr"),
+        ]);
+        let file = ConfigFile {
+            boot_packages: vec![String::from("grub")],
+            ..Default::default()
+        };
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(
+            config.mode,
+            Mode::Kernel {
+                packages: vec![String::from("grub"), String::from("efibootmgr")]
+            }
+        )
+    }
+
+    #[test]
+    fn should_create_config_from_args_reboot() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("reboot"),
+            String::from("--boots"),
+            String::from("/tmp/boots.json"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(config.mode, Mode::Reboot);
+        assert_eq!(config.boots, Some(String::from("/tmp/boots.json")))
+    }
+
+    #[test]
+    fn should_create_config_from_args_columns() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("-o"),
+            String::from("compact"),
+            String::from("--columns"),
+            String::from("package,from,to,duration"),
+        ]);
+        let config = Config::from_arg_matches(&matches, &ConfigFile::default()).unwrap();
+        assert_eq!(
+            config.format,
+            Format::Compact {
+                with_colors: true,
+                without_details: false,
+                columns: vec![Column::Package, Column::From, Column::To, Column::Duration]
+            }
+        );
+        assert!(config.durations)
+    }
+
+    #[test]
+    fn should_create_config_from_args_color() {
+        let matches = parse_args(&[
+            String::from("pkghist"),
+            String::from("--color"),
+            String::from("never"),
+        ]);
+        let file = ConfigFile {
+            color: Some(String::from("always")),
+            ..Default::default()
+        };
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.color, ColorMode::Never);
+        assert!(!config.format.with_colors());
+
+        let matches = parse_args(&[String::from("pkghist")]);
+        let config = Config::from_arg_matches(&matches, &file).unwrap();
+        assert_eq!(config.color, ColorMode::Always);
+        assert!(config.format.with_colors())
+    }
+
+    #[test]
+    fn should_read_palette() {
+        let mut colors = BTreeMap::new();
+        colors.insert(String::from("removed"), String::from("light-red"));
+        colors.insert(String::from("Upgraded"), String::from("208"));
+        let palette = Palette::from_config(&colors).unwrap();
+        assert_eq!(palette.color(&Action::Removed).map(|c| c.0), Some(9));
+        assert_eq!(palette.color(&Action::Upgraded).map(|c| c.0), Some(208));
+        assert!(palette.color(&Action::Installed).is_none());
+
+        colors.insert(String::from("removed"), String::from("pink"));
+        assert!(Palette::from_config(&colors).is_err());
+        colors.clear();
+        colors.insert(String::from("purged"), String::from("red"));
+        assert!(Palette::from_config(&colors).is_err())
+    }
 }
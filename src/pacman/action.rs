@@ -1,8 +1,11 @@
 use std::str::FromStr;
 
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
 use crate::error::{Error, ErrorDetail};
 
-#[derive(Debug, Eq, PartialEq, PartialOrd, Clone)]
+#[derive(Debug, Eq, PartialEq, PartialOrd, Clone, Hash)]
 pub enum Action {
     Installed,
     Reinstalled,
@@ -42,6 +45,20 @@ impl FromStr for Action {
     }
 }
 
+impl Serialize for Action {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Action {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse()
+            .map_err(|_| D::Error::custom(format!("invalid action '{}'", s)))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -49,15 +66,15 @@ mod tests {
     #[test]
     fn should_be_removed() {
         let removed = Action::Removed;
-        assert_eq!(removed.is_removed(), true);
-        assert_eq!(removed.is_installed(), false)
+        assert!(removed.is_removed());
+        assert!(!removed.is_installed())
     }
 
     #[test]
     fn should_be_installed() {
         let installed = Action::Upgraded;
-        assert_eq!(installed.is_installed(), true);
-        assert_eq!(installed.is_removed(), false)
+        assert!(installed.is_installed());
+        assert!(!installed.is_removed())
     }
 
     #[test]
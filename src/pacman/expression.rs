@@ -0,0 +1,439 @@
+use std::cmp::Ordering;
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use regex::Regex;
+
+use crate::error::{Error, ErrorDetail};
+use crate::pacman::action::Action;
+use crate::pacman::version::Version;
+use crate::pacman::PacmanEvent;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Match,
+    NotMatch,
+}
+
+impl Operator {
+    fn holds(&self, ordering: Ordering) -> bool {
+        match self {
+            Operator::Eq => ordering == Ordering::Equal,
+            Operator::Ne => ordering != Ordering::Equal,
+            Operator::Lt => ordering == Ordering::Less,
+            Operator::Le => ordering != Ordering::Greater,
+            Operator::Gt => ordering == Ordering::Greater,
+            Operator::Ge => ordering != Ordering::Less,
+            Operator::Match | Operator::NotMatch => false,
+        }
+    }
+
+    fn is_ordering(&self) -> bool {
+        !matches!(self, Operator::Match | Operator::NotMatch)
+    }
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let operator = match self {
+            Operator::Eq => "=",
+            Operator::Ne => "!=",
+            Operator::Lt => "<",
+            Operator::Le => "<=",
+            Operator::Gt => ">",
+            Operator::Ge => ">=",
+            Operator::Match => "~",
+            Operator::NotMatch => "!~",
+        };
+        write!(f, "{}", operator)
+    }
+}
+
+/// A day compares against the day of the event, a date with a time against the exact date.
+#[derive(Debug, Clone, PartialEq)]
+enum DateValue {
+    Day(NaiveDate),
+    Time(NaiveDateTime),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Operator(Operator),
+    Word(String),
+    Quoted(String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Open => write!(f, "'('"),
+            Token::Close => write!(f, "')'"),
+            Token::Operator(operator) => write!(f, "'{}'", operator),
+            Token::Word(word) => write!(f, "'{}'", word),
+            Token::Quoted(value) => write!(f, "'{}'", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Name(Operator, String),
+    NameMatches(Regex),
+    Action(Action),
+    Date(Operator, DateValue),
+    Version(Operator, Version),
+    VersionMatches(Regex),
+}
+
+impl Node {
+    fn matches(&self, pacman_event: &PacmanEvent) -> bool {
+        match self {
+            Node::And(left, right) => left.matches(pacman_event) && right.matches(pacman_event),
+            Node::Or(left, right) => left.matches(pacman_event) || right.matches(pacman_event),
+            Node::Not(node) => !node.matches(pacman_event),
+            Node::Name(operator, name) => {
+                operator.holds(pacman_event.package.as_str().cmp(name.as_str()))
+            }
+            Node::NameMatches(regex) => regex.is_match(&pacman_event.package),
+            Node::Action(action) => pacman_event.action == *action,
+            Node::Date(operator, DateValue::Day(day)) => {
+                operator.holds(pacman_event.date.date().cmp(day))
+            }
+            Node::Date(operator, DateValue::Time(time)) => {
+                operator.holds(pacman_event.date.cmp(time))
+            }
+            Node::Version(operator, version) => {
+                match pacman_event.printable_version().parse::<Version>() {
+                    Ok(v) => operator.holds(v.vercmp(version)),
+                    Err(_) => false,
+                }
+            }
+            Node::VersionMatches(regex) => regex.is_match(&pacman_event.printable_version()),
+        }
+    }
+}
+
+/// A boolean expression over pacman events, e.g.
+/// `name~'^python' and action=upgraded and date>2024-01-01 and not name~'-git$'`.
+///
+/// The fields `name`, `action`, `date` (`YYYY-MM-DD` or `'YYYY-MM-DD HH:MM'`) and `version` are compared
+/// with `=`, `!=`, `<`, `<=`, `>` and `>=`, `name` and `version` are matched against regexes with `~` and `!~`.
+/// Conditions are combined with `not`, `and` and `or` (in this order of precedence) and grouped with parentheses.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    root: Node,
+}
+
+impl Expression {
+    pub fn matches(&self, pacman_event: &PacmanEvent) -> bool {
+        self.root.matches(pacman_event)
+    }
+}
+
+fn invalid_expression(expression: &str, msg: String) -> Error {
+    Error::new(ErrorDetail::InvalidConfig {
+        msg: format!("Invalid expression '{}': {}", expression, msg),
+    })
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => continue,
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            '=' => tokens.push(Token::Operator(Operator::Eq)),
+            '~' => tokens.push(Token::Operator(Operator::Match)),
+            '<' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Operator(Operator::Le)),
+            '<' => tokens.push(Token::Operator(Operator::Lt)),
+            '>' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Operator(Operator::Ge)),
+            '>' => tokens.push(Token::Operator(Operator::Gt)),
+            '!' if chars.next_if_eq(&'=').is_some() => tokens.push(Token::Operator(Operator::Ne)),
+            '!' if chars.next_if_eq(&'~').is_some() => {
+                tokens.push(Token::Operator(Operator::NotMatch))
+            }
+            '!' => return Err(invalid_expression(s, String::from("Expected '!=' or '!~'"))),
+            '\'' | '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(v) => value.push(v),
+                        None => return Err(invalid_expression(s, format!("Unclosed quote {}", c))),
+                    }
+                }
+                tokens.push(Token::Quoted(value))
+            }
+            c => {
+                let mut word = String::from(c);
+                while let Some(w) =
+                    chars.next_if(|w| !w.is_whitespace() && !"()=~<>!'\"".contains(*w))
+                {
+                    word.push(w)
+                }
+                tokens.push(Token::Word(word))
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/*
+ * expression := and ("or" and)*
+ * and        := not ("and" not)*
+ * not        := "not" not | "(" expression ")" | field operator value
+ */
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        match self.tokens.get(self.position) {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn error(&self, msg: String) -> Error {
+        invalid_expression(self.expression, msg)
+    }
+
+    fn or(&mut self) -> Result<Node, Error> {
+        let mut expression = self.and()?;
+        while self.next_if_keyword("or") {
+            expression = Node::Or(Box::new(expression), Box::new(self.and()?))
+        }
+        Ok(expression)
+    }
+
+    fn and(&mut self) -> Result<Node, Error> {
+        let mut expression = self.not()?;
+        while self.next_if_keyword("and") {
+            expression = Node::And(Box::new(expression), Box::new(self.not()?))
+        }
+        Ok(expression)
+    }
+
+    fn not(&mut self) -> Result<Node, Error> {
+        if self.next_if_keyword("not") {
+            return Ok(Node::Not(Box::new(self.not()?)));
+        }
+        match self.next() {
+            Some(Token::Open) => {
+                let expression = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expression),
+                    _ => Err(self.error(String::from("Expected ')'"))),
+                }
+            }
+            Some(Token::Word(field)) => {
+                let operator = match self.next() {
+                    Some(Token::Operator(operator)) => operator,
+                    _ => return Err(self.error(format!("Expected an operator after '{}'", field))),
+                };
+                let value = match self.next() {
+                    Some(Token::Word(value)) | Some(Token::Quoted(value)) => value,
+                    _ => {
+                        return Err(
+                            self.error(format!("Expected a value after '{}{}'", field, operator))
+                        )
+                    }
+                };
+                self.condition(&field, operator, &value)
+            }
+            Some(token) => Err(self.error(format!("Unexpected {}", token))),
+            None => Err(self.error(String::from("Unexpected end"))),
+        }
+    }
+
+    fn condition(&self, field: &str, operator: Operator, value: &str) -> Result<Node, Error> {
+        let regex = |value: &str| {
+            Regex::new(value).map_err(|e| self.error(format!("Invalid regex '{}': {}", value, e)))
+        };
+        let unsupported =
+            || self.error(format!("'{}' can't be compared with '{}'", field, operator));
+        let condition = match (field, operator) {
+            ("name", Operator::Match | Operator::NotMatch) => Node::NameMatches(regex(value)?),
+            ("name", _) => Node::Name(operator, String::from(value)),
+            ("action", Operator::Eq | Operator::Ne) => {
+                Node::Action(value.parse().map_err(|_| {
+                    self.error(format!(
+                        "Unknown action '{}', expected one of installed, reinstalled, upgraded, downgraded, removed",
+                        value
+                    ))
+                })?)
+            }
+            ("action", _) => return Err(unsupported()),
+            ("date", operator) if operator.is_ordering() => {
+                let date = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M")
+                    .map(DateValue::Time)
+                    .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(DateValue::Day))
+                    .map_err(|_| {
+                        self.error(format!(
+                            "Invalid date '{}' [Format: \"YYYY-MM-DD\" or \"YYYY-MM-DD HH:MM\"]",
+                            value
+                        ))
+                    })?;
+                Node::Date(operator, date)
+            }
+            ("date", _) => return Err(unsupported()),
+            ("version", Operator::Match | Operator::NotMatch) => {
+                Node::VersionMatches(regex(value)?)
+            }
+            ("version", _) => Node::Version(
+                operator,
+                value
+                    .parse()
+                    .map_err(|_| self.error(format!("Invalid version '{}'", value)))?,
+            ),
+            _ => {
+                return Err(self.error(format!(
+                    "Unknown field '{}', expected one of name, action, date, version",
+                    field
+                )))
+            }
+        };
+        // `!=` can't compare actions and `!~` doesn't compile into a regex, both negate the condition
+        match (&condition, operator) {
+            (Node::Action(_), Operator::Ne) => Ok(Node::Not(Box::new(condition))),
+            (_, Operator::NotMatch) => Ok(Node::Not(Box::new(condition))),
+            _ => Ok(condition),
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            expression: s,
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        let root = parser.or()?;
+        match parser.next() {
+            None => Ok(Expression { root }),
+            Some(token) => Err(parser.error(format!("Unexpected {}", token))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pacman_event(line: &str) -> PacmanEvent {
+        line.parse().unwrap()
+    }
+
+    fn matches(expression: &str, line: &str) -> bool {
+        expression
+            .parse::<Expression>()
+            .unwrap()
+            .matches(&pacman_event(line))
+    }
+
+    #[test]
+    fn should_match_combined_conditions() {
+        let expression =
+            "name~'^python' and action=upgraded and date>2024-01-01 and not name~'-git$'";
+        assert!(matches(
+            expression,
+            "[2024-01-02 10:00] [ALPM] upgraded python-requests (2.31.0-1 -> 2.31.0-2)"
+        ));
+        assert!(!matches(
+            expression,
+            "[2024-01-02 10:00] [ALPM] upgraded python-foo-git (1.0-1 -> 1.1-1)"
+        ));
+        assert!(!matches(
+            expression,
+            "[2024-01-01 10:00] [ALPM] upgraded python-requests (2.31.0-1 -> 2.31.0-2)"
+        ));
+        assert!(!matches(
+            expression,
+            "[2024-01-02 10:00] [ALPM] installed python-requests (2.31.0-2)"
+        ))
+    }
+
+    #[test]
+    fn should_bind_and_before_or() {
+        let expression = "name=vim or name=zsh and action=removed";
+        let line = |action: &str, package: &str| {
+            format!("[2024-01-02 10:00] [ALPM] {} {} (1.0-1)", action, package)
+        };
+        assert!(matches(expression, &line("installed", "vim")));
+        assert!(!matches(expression, &line("installed", "zsh")));
+        assert!(matches(expression, &line("removed", "zsh")));
+        assert!(!matches(
+            "(name=vim or name=zsh) and action=removed",
+            &line("installed", "vim")
+        ))
+    }
+
+    #[test]
+    fn should_compare_dates_and_versions() {
+        let line = "[2024-01-02 10:00] [ALPM] upgraded linux (6.6.9-1 -> 6.7.1-1)";
+        assert!(matches("date=2024-01-02", line));
+        assert!(matches("date>='2024-01-02 10:00'", line));
+        assert!(!matches("date<'2024-01-02 10:00'", line));
+        assert!(matches("version>=6.7", line));
+        assert!(matches("version<6.10", line));
+        assert!(matches("version~'^6\\.7'", line));
+        assert!(matches("action!=removed and name!=linux-lts", line));
+        assert!(matches("NOT version!~'-1$'", line))
+    }
+
+    #[test]
+    fn should_not_parse_invalid_expressions() {
+        for expression in [
+            "",
+            "name",
+            "name~",
+            "name~'^python",
+            "(name=vim",
+            "name=vim)",
+            "name=vim and",
+            "size>10",
+            "action=purged",
+            "action<removed",
+            "date~2024",
+            "date>yesterday",
+            "name~'('",
+            "name ! vim",
+        ] {
+            let result = expression.parse::<Expression>();
+            assert_eq!(
+                result.unwrap_err().exit_code(),
+                3,
+                "'{}' should be invalid",
+                expression
+            )
+        }
+    }
+}
@@ -5,6 +5,8 @@ use chrono::NaiveDateTime;
 use regex::Regex;
 
 use crate::opt::Config;
+use crate::pacman::action::Action;
+use crate::pacman::expression::Expression;
 use crate::pacman::group::Group;
 use crate::pacman::newest::Newest;
 use crate::pacman::range;
@@ -18,6 +20,12 @@ pub trait Filter {
     fn without_removed(&self) -> HashMap<&String, Vec<&Self::Event>>;
 
     fn filter_packages(&self, config: &Config) -> HashMap<&String, Vec<&Self::Event>>;
+
+    /// Like `filter_packages`, but keeps the packages removed by now unless only those are asked for.
+    fn filter_events(&self, config: &Config) -> HashMap<&String, Vec<&Self::Event>>;
+
+    /// All events of the packages passing the filters, removed packages included.
+    fn select_packages(&self, config: &Config) -> HashMap<&String, Vec<&Self::Event>>;
 }
 
 impl Filter for Vec<PacmanEvent> {
@@ -55,25 +63,56 @@ impl Filter for Vec<PacmanEvent> {
         } else {
             self.group()
         };
+        filter_groups(packages, config)
+    }
 
-        let mut filtered_packages = HashMap::new();
-        for (package, events) in packages {
-            let filtered_events = filter_events(events.clone(), &config.after);
-            if !filtered_events.is_empty()
-                && (config.filters.is_empty()
-                    || matches_filter(package, config.exclude, &config.filters))
-            {
-                filtered_packages.insert(package, filtered_events);
-            }
-        }
+    fn filter_events(&self, config: &Config) -> HashMap<&String, Vec<&Self::Event>> {
+        let packages = if config.removed_only {
+            self.without_installed()
+        } else {
+            self.group()
+        };
+        filter_groups(packages, config)
+    }
 
-        limit_pacman_events(
-            &range::range(&filtered_packages, &config.direction),
-            config.limit,
-        )
+    fn select_packages(&self, config: &Config) -> HashMap<&String, Vec<&Self::Event>> {
+        self.group()
+            .into_iter()
+            .filter(|(package, _)| matches_filters(package, config))
+            .collect()
     }
 }
 
+fn filter_groups<'a>(
+    packages: HashMap<&'a String, Vec<&'a PacmanEvent>>,
+    config: &Config,
+) -> HashMap<&'a String, Vec<&'a PacmanEvent>> {
+    let mut filtered_packages = HashMap::new();
+    for (package, events) in packages {
+        let filtered_events = filter_events_after(events.clone(), &config.after);
+        let filtered_events = filter_events_before(filtered_events, &config.before);
+        let filtered_events = filter_actions(filtered_events, &config.actions);
+        let filtered_events = filter_expression(filtered_events, &config.expression);
+        if !filtered_events.is_empty() && matches_filters(package, config) {
+            filtered_packages.insert(package, filtered_events);
+        }
+    }
+
+    limit_pacman_events(
+        &range::range(&filtered_packages, &config.direction),
+        config.limit,
+    )
+}
+
+/// Whether the package is selected by the filters of the config, every package is without filters.
+pub fn matches_filters(package: &str, config: &Config) -> bool {
+    (config.filters.is_empty() || matches_filter(package, config.exclude, &config.filters))
+        && config
+            .search
+            .as_ref()
+            .map_or(true, |search| search.is_match(package))
+}
+
 /*
  * - if exclude is false -> any filter must match
  * - if exclude is true -> all filters must match
@@ -86,7 +125,7 @@ fn matches_filter(package: &str, exclude: bool, filters: &[Regex]) -> bool {
     }
 }
 
-fn filter_events<'a>(
+fn filter_events_after<'a>(
     events: Vec<&'a PacmanEvent>,
     after: &Option<NaiveDateTime>,
 ) -> Vec<&'a PacmanEvent> {
@@ -96,6 +135,40 @@ fn filter_events<'a>(
     }
 }
 
+fn filter_events_before<'a>(
+    events: Vec<&'a PacmanEvent>,
+    before: &Option<NaiveDateTime>,
+) -> Vec<&'a PacmanEvent> {
+    match before {
+        Some(b) => events.into_iter().filter(|event| event.date < *b).collect(),
+        None => events,
+    }
+}
+
+fn filter_actions<'a>(events: Vec<&'a PacmanEvent>, actions: &[Action]) -> Vec<&'a PacmanEvent> {
+    if actions.is_empty() {
+        events
+    } else {
+        events
+            .into_iter()
+            .filter(|event| actions.contains(&event.action))
+            .collect()
+    }
+}
+
+fn filter_expression<'a>(
+    events: Vec<&'a PacmanEvent>,
+    expression: &Option<Expression>,
+) -> Vec<&'a PacmanEvent> {
+    match expression {
+        Some(e) => events
+            .into_iter()
+            .filter(|event| e.matches(event))
+            .collect(),
+        None => events,
+    }
+}
+
 fn limit_pacman_events<'a>(
     packages: &HashMap<&'a String, Vec<&'a PacmanEvent>>,
     limit: Option<u32>,
@@ -123,6 +196,12 @@ pub fn is_relevant_package(filters: &[String], package: &str) -> bool {
 }
 
 #[cfg(test)]
+#[allow(
+    clippy::bool_assert_comparison,
+    clippy::unnecessary_mut_passed,
+    clippy::vec_init_then_push,
+    clippy::zero_prefixed_literal
+)]
 mod tests {
     use std::fs;
     use std::fs::File;
@@ -135,7 +214,6 @@ mod tests {
     use crate::pacman;
 
     use super::*;
-    use crate::pacman::action::Action;
 
     #[test]
     fn should_match_package_starting_with() {
@@ -177,7 +255,7 @@ mod tests {
         let mut config = Config::new();
         config.logfile = file_name.clone();
 
-        let pacman_events = pacman::from_file(Path::new(&file_name))
+        let pacman_events = pacman::from_file(Path::new(&file_name), |_| {})
             .unwrap_or_else(|_| panic!("Unable to open {}", &file_name));
 
         let groups = pacman_events.filter_packages(&config);
@@ -212,7 +290,7 @@ mod tests {
         config.logfile = file_name.clone();
         config.filters = filters;
 
-        let pacman_events = pacman::from_file(Path::new(&file_name))
+        let pacman_events = pacman::from_file(Path::new(&file_name), |_| {})
             .unwrap_or_else(|_| panic!("Unable to open {}", &file_name));
 
         let groups = pacman_events.filter_packages(&config);
@@ -248,7 +326,7 @@ mod tests {
         config.exclude = true;
         config.filters = filters;
 
-        let pacman_events = pacman::from_file(Path::new(&file_name))
+        let pacman_events = pacman::from_file(Path::new(&file_name), |_| {})
             .unwrap_or_else(|_| panic!("Unable to open {}", &file_name));
 
         let groups = pacman_events.filter_packages(&config);
@@ -285,7 +363,7 @@ mod tests {
         config.filters = filters;
         config.removed_only = true;
 
-        let pacman_events = pacman::from_file(Path::new(&file_name))
+        let pacman_events = pacman::from_file(Path::new(&file_name), |_| {})
             .unwrap_or_else(|_| panic!("Unable to open {}", &file_name));
 
         let groups = pacman_events.filter_packages(&config);
@@ -317,7 +395,7 @@ mod tests {
         config.filters = Vec::new();
         config.with_removed = true;
 
-        let pacman_events = pacman::from_file(Path::new(&file_name))
+        let pacman_events = pacman::from_file(Path::new(&file_name), |_| {})
             .unwrap_or_else(|_| panic!("Unable to open {}", &file_name));
 
         let groups = pacman_events.filter_packages(&config);
@@ -437,6 +515,35 @@ mod tests {
         )
     }
 
+    #[test]
+    fn should_keep_events_of_removed_packages() {
+        // given
+        let pacman_events = some_pacman_events();
+        let config = Config::new();
+
+        // when
+        let groups = pacman_events.filter_events(&config);
+
+        // then
+        assert!(groups.contains_key(&String::from("no-longer-used")));
+        assert_eq!(groups.values().flatten().count(), 6)
+    }
+
+    #[test]
+    fn should_keep_only_events_of_removed_packages() {
+        // given
+        let pacman_events = some_pacman_events();
+        let mut config = Config::new();
+        config.removed_only = true;
+
+        // when
+        let groups = pacman_events.filter_events(&config);
+
+        // then
+        assert_eq!(groups.len(), 1);
+        assert!(groups.contains_key(&String::from("no-longer-used")))
+    }
+
     #[test]
     fn should_be_relevant_when_filters_are_empty() {
         let filters = Vec::new();
@@ -469,7 +576,40 @@ mod tests {
     fn should_filter_events_before_date() {
         let pacman_events = some_pacman_events();
         let refs = pacman_events.iter().collect();
-        let filtered = filter_events(
+        let filtered = filter_events_after(
+            refs,
+            &Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2019, 8, 30).unwrap(),
+                NaiveTime::from_hms_opt(12, 31, 0).unwrap(),
+            )),
+        );
+
+        assert_eq!(filtered.len(), 2)
+    }
+
+    #[test]
+    fn should_filter_events_after_date() {
+        let pacman_events = some_pacman_events();
+        let refs = pacman_events.iter().collect();
+        let filtered = filter_events_after(
+            refs,
+            &Some(NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2019, 8, 30).unwrap(),
+                NaiveTime::from_hms_opt(12, 30, 0).unwrap(),
+            )),
+        );
+
+        assert_eq!(filtered.len(), 3);
+        assert!(filtered
+            .iter()
+            .all(|e| e.date.time() > NaiveTime::from_hms_opt(12, 30, 0).unwrap()))
+    }
+
+    #[test]
+    fn should_keep_events_before_date() {
+        let pacman_events = some_pacman_events();
+        let refs = pacman_events.iter().collect();
+        let filtered = filter_events_before(
             refs,
             &Some(NaiveDateTime::new(
                 NaiveDate::from_ymd_opt(2019, 8, 30).unwrap(),
@@ -477,14 +617,37 @@ mod tests {
             )),
         );
 
+        assert_eq!(filtered.len(), 3);
+        assert!(filtered
+            .iter()
+            .all(|e| e.date.time() < NaiveTime::from_hms_opt(12, 31, 0).unwrap()))
+    }
+
+    #[test]
+    fn should_filter_actions() {
+        let pacman_events = some_pacman_events();
+        let refs = pacman_events.iter().collect();
+        let filtered = filter_actions(refs, &[Action::Removed]);
+
         assert_eq!(filtered.len(), 2)
     }
 
+    #[test]
+    fn should_filter_expression() {
+        let pacman_events = some_pacman_events();
+        let refs = pacman_events.iter().collect();
+        let expression = "name~'package$' and not action=removed".parse().unwrap();
+        let filtered = filter_expression(refs, &Some(expression));
+
+        assert_eq!(filtered.len(), 4);
+        assert!(filtered.iter().all(|e| e.action == Action::Installed))
+    }
+
     #[test]
     fn should_filter_no_events() {
         let pacman_events = some_pacman_events();
         let refs = pacman_events.iter().collect();
-        let filtered = filter_events(refs, &None);
+        let filtered = filter_events_after(refs, &None);
 
         assert_eq!(filtered.len(), 6)
     }
@@ -18,9 +18,7 @@ impl Group for Vec<PacmanEvent> {
                 new_vec.push(event);
                 groups.insert(&event.package, new_vec);
             } else {
-                let mut value = Vec::new();
-                value.push(event);
-                groups.insert(&event.package, value);
+                groups.insert(&event.package, vec![event]);
             }
         }
         groups
@@ -4,20 +4,24 @@ use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::str::FromStr;
 
-use chrono::DateTime;
-use chrono::NaiveDateTime;
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeZone, Utc};
 
 use lazy_static::*;
 use regex::*;
 
 use crate::error::{Error, ErrorDetail};
 use crate::pacman::action::Action;
+use crate::pacman::version::Version;
+use crate::source::{LogSource, OnSkippedLine, SkippedLine};
 
 pub mod action;
+pub mod expression;
 pub mod filter;
 pub mod group;
 pub mod newest;
 pub mod range;
+pub mod transaction;
+pub mod version;
 
 lazy_static! {
     static ref REGEX: Regex = Regex::new(r"^\[(?P<date>(\d{4}-\d{2}-\d{2}\s\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}[+-]\d{4}))\]\s\[.+\]\s(?P<action>upgraded|installed|removed|reinstalled|downgraded)\s(?P<package>.+)\s\((?P<from>.+?)(\s->\s(?P<to>.+))?\)").unwrap();
@@ -30,6 +34,8 @@ pub struct PacmanEvent {
     pub package: String,
     pub from: String,
     pub to: Option<String>,
+    /// The UTC offset of `date`, only logged by pacman since 5.2
+    pub offset: Option<FixedOffset>,
 }
 
 impl PacmanEvent {
@@ -46,6 +52,7 @@ impl PacmanEvent {
             package,
             from,
             to,
+            offset: None,
         }
     }
 
@@ -56,6 +63,24 @@ impl PacmanEvent {
             self.from.clone()
         }
     }
+
+    /// The version after the event and the one it replaced, e.g. an empty version can't be parsed.
+    pub fn versions(&self) -> Result<(Version, Option<Version>), Error> {
+        let version = self.printable_version().parse()?;
+        let from = match self.to {
+            Some(_) => Some(self.from.parse()?),
+            None => None,
+        };
+        Ok((version, from))
+    }
+
+    /// The date including its UTC offset, dates logged without an offset are taken as local time.
+    pub fn timestamp(&self) -> DateTime<FixedOffset> {
+        match self.offset {
+            Some(offset) => offset.from_local_datetime(&self.date).unwrap(),
+            None => local_timestamp(&self.date),
+        }
+    }
 }
 
 impl Ord for PacmanEvent {
@@ -86,11 +111,10 @@ impl FromStr for PacmanEvent {
                         Action::from_str(captures.name("action").unwrap().as_str()).unwrap();
                     let package = String::from(captures.name("package").unwrap().as_str());
                     let from = String::from(captures.name("from").unwrap().as_str());
-                    let to = match captures.name("to") {
-                        Some(to) => Some(String::from(to.as_str())),
-                        None => None,
-                    };
-                    Ok(PacmanEvent::new(date, action, package, from, to))
+                    let to = captures.name("to").map(|to| String::from(to.as_str()));
+                    let mut pacman_event = PacmanEvent::new(date, action, package, from, to);
+                    pacman_event.offset = parse_offset(captures.name("date").unwrap().as_str());
+                    Ok(pacman_event)
                 }
                 None => Err(Error::new(ErrorDetail::InvalidFormat)),
             }
@@ -111,7 +135,39 @@ fn parse_date(date_str: &str) -> NaiveDateTime {
     }
 }
 
-pub fn from_file(path: &Path) -> std::io::Result<Vec<PacmanEvent>> {
+/// Attaches the offset of the local time zone, falling back to UTC for nonexistent local times.
+pub fn local_timestamp(date: &NaiveDateTime) -> DateTime<FixedOffset> {
+    let offset = Local
+        .from_local_datetime(date)
+        .earliest()
+        .map_or(Utc.fix(), |d| d.offset().fix());
+    offset.from_local_datetime(date).unwrap()
+}
+
+fn parse_offset(date_str: &str) -> Option<FixedOffset> {
+    DateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S%z")
+        .ok()
+        .map(|date_time| *date_time.offset())
+}
+
+/// Pacman's `/var/log/pacman.log`
+pub struct Pacman;
+
+impl LogSource for Pacman {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/pacman.log"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        from_file(path, on_skipped_line)
+    }
+}
+
+pub fn from_file(path: &Path, on_skipped_line: OnSkippedLine) -> std::io::Result<Vec<PacmanEvent>> {
     let f = File::open(path)?;
     let file = BufReader::new(&f);
     let pacman_events: Vec<PacmanEvent> =
@@ -126,7 +182,7 @@ pub fn from_file(path: &Path) -> std::io::Result<Vec<PacmanEvent>> {
                     Err(_) => current,
                 },
                 Err(e) => {
-                    eprintln!("Skipping line #{} ({e})", idx + 1);
+                    on_skipped_line(&SkippedLine::new(path, idx, e.to_string()));
                     current
                 }
             });
@@ -227,6 +283,7 @@ mod tests {
             package: String::from("libva"),
             from: String::from("2.4.1-1"),
             to: Some(String::from("2.5.0-1")),
+            offset: None,
         };
         assert_eq!(line, expected_pacman_event)
     }
@@ -245,6 +302,7 @@ mod tests {
             package: String::from("ansible"),
             from: String::from("2.8.1-1"),
             to: None,
+            offset: None,
         };
         assert_eq!(line, exptected_pacman_event)
     }
@@ -264,6 +322,7 @@ mod tests {
             package: String::from("mps-youtube"),
             from: String::from("0.2.8-2"),
             to: Some(String::from("0.2.8-1")),
+            offset: None,
         };
         assert_eq!(line, expected_pacman_event)
     }
@@ -307,13 +366,13 @@ mod tests {
     #[test]
     fn should_not_extract_a_pacman_event() {
         let r = PacmanEvent::from_str("[2019-07-04 14:05] I AM NOT MATCHING");
-        assert_eq!(r.is_err(), true)
+        assert!(r.is_err())
     }
 
     #[test]
     fn should_result_in_an_error() {
-        let res = from_file(path::Path::new(&String::from("/not/found")));
-        assert_eq!(res.is_err(), true)
+        let res = from_file(path::Path::new(&String::from("/not/found")), |_| {});
+        assert!(res.is_err())
     }
 
     #[test]
@@ -325,7 +384,7 @@ mod tests {
         )
         .unwrap();
 
-        let pacman_events = from_file(&file.path().unwrap()).unwrap();
+        let pacman_events = from_file(&file.path().unwrap(), |_| {}).unwrap();
 
         assert_eq!(pacman_events.len(), 3);
 
@@ -351,7 +410,7 @@ mod tests {
         )
         .unwrap();
 
-        let pacman_events = from_file(Path::new(&file_name)).unwrap();
+        let pacman_events = from_file(Path::new(&file_name), |_| {}).unwrap();
 
         assert_eq!(pacman_events.len(), 0);
 
@@ -42,6 +42,7 @@ pub fn range<'a, S: BuildHasher + Default>(
 }
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push, clippy::zero_prefixed_literal)]
 mod tests {
     use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
@@ -127,7 +128,7 @@ mod tests {
 
         // then
         assert_eq!(filtered.keys().len(), 2);
-        assert!(filtered.get(&String::from("b")).is_some());
-        assert!(filtered.get(&String::from("c")).is_some())
+        assert!(filtered.contains_key(&String::from("b")));
+        assert!(filtered.contains_key(&String::from("c")))
     }
 }
@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::NaiveDateTime;
+
+use lazy_static::*;
+use regex::*;
+
+use crate::error::{Error, ErrorDetail};
+use crate::pacman::{parse_date, PacmanEvent};
+use crate::source::{OnSkippedLine, SkippedLine};
+
+lazy_static! {
+    static ref RUNNING: Regex = Regex::new(r"^\[(?P<date>(\d{4}-\d{2}-\d{2}\s\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}[+-]\d{4}))\]\s\[PACMAN\]\sRunning\s'(?P<command>.*)'").unwrap();
+}
+
+/// All events caused by a single `[PACMAN] Running` line.
+///
+/// Events logged before the first `Running` line end up in a transaction
+/// without a command.
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct Transaction {
+    pub date: NaiveDateTime,
+    pub command: Option<String>,
+    pub events: Vec<PacmanEvent>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Operation {
+    pub sync: bool,
+    pub refresh: bool,
+    pub sysupgrade: bool,
+}
+
+impl Transaction {
+    pub fn new(date: NaiveDateTime, command: Option<String>) -> Transaction {
+        Transaction {
+            date,
+            command,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn operation(&self) -> Operation {
+        match &self.command {
+            Some(command) => parse_operation(command),
+            None => Operation::default(),
+        }
+    }
+}
+
+fn parse_operation(command: &str) -> Operation {
+    let mut operation = Operation::default();
+    for arg in command.split_whitespace().skip(1) {
+        if arg == "--" {
+            break;
+        } else if let Some(long) = arg.strip_prefix("--") {
+            match long {
+                "sync" => operation.sync = true,
+                "refresh" => operation.refresh = true,
+                "sysupgrade" => operation.sysupgrade = true,
+                _ => {}
+            }
+        } else if let Some(short) = arg.strip_prefix('-') {
+            for c in short.chars() {
+                match c {
+                    'S' => operation.sync = true,
+                    'y' => operation.refresh = true,
+                    'u' => operation.sysupgrade = true,
+                    _ => {}
+                }
+            }
+        }
+    }
+    operation
+}
+
+/// Reads the transactions of the given logfile, failing with `ErrorDetail::UnreadableLogfile`.
+pub fn from_logfile(
+    logfile: &str,
+    on_skipped_line: OnSkippedLine,
+) -> Result<Vec<Transaction>, Error> {
+    from_file(Path::new(logfile), on_skipped_line).map_err(|e| {
+        Error::new(ErrorDetail::UnreadableLogfile {
+            path: String::from(logfile),
+            msg: e.to_string(),
+        })
+    })
+}
+
+pub fn from_file(path: &Path, on_skipped_line: OnSkippedLine) -> std::io::Result<Vec<Transaction>> {
+    let f = File::open(path)?;
+    let file = BufReader::new(&f);
+    let mut transactions: Vec<Transaction> = Vec::new();
+    for (idx, l) in file.lines().enumerate() {
+        match l {
+            Ok(line) => {
+                if let Some(captures) = RUNNING.captures(&line) {
+                    let date = parse_date(captures.name("date").unwrap().as_str());
+                    let command = String::from(captures.name("command").unwrap().as_str());
+                    transactions.push(Transaction::new(date, Some(command)));
+                } else if let Ok(pacman_event) = PacmanEvent::from_str(&line) {
+                    match transactions.last_mut() {
+                        Some(transaction) => transaction.events.push(pacman_event),
+                        None => {
+                            let mut transaction = Transaction::new(pacman_event.date, None);
+                            transaction.events.push(pacman_event);
+                            transactions.push(transaction)
+                        }
+                    }
+                }
+            }
+            Err(e) => on_skipped_line(&SkippedLine::new(path, idx, e.to_string())),
+        }
+    }
+    Ok(transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+
+    use filepath::FilePath;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_operation_short_flags() {
+        assert_eq!(
+            parse_operation("pacman -Syu"),
+            Operation {
+                sync: true,
+                refresh: true,
+                sysupgrade: true
+            }
+        )
+    }
+
+    #[test]
+    fn should_parse_operation_long_flags() {
+        assert_eq!(
+            parse_operation("/usr/bin/pacman --sync --refresh foo"),
+            Operation {
+                sync: true,
+                refresh: true,
+                sysupgrade: false
+            }
+        )
+    }
+
+    #[test]
+    fn should_not_parse_operation_from_package_names() {
+        assert_eq!(
+            parse_operation("pacman -S -- -yu"),
+            Operation {
+                sync: true,
+                refresh: false,
+                sysupgrade: false
+            }
+        )
+    }
+
+    #[test]
+    fn should_group_events_by_command() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "[2019-07-14 21:30] [ALPM] installed bash (5.0.0-1)\n\
+             [2019-07-14 21:33] [PACMAN] Running 'pacman -Sy feh'\n\
+             [2019-07-14 21:33] [PACMAN] synchronizing package lists\n\
+             [2019-07-14 21:33] [ALPM] transaction started\n\
+             [2019-07-14 21:33] [ALPM] upgraded feh (3.1.3-1 -> 3.2-1)\n\
+             [2019-07-14 21:33] [ALPM] transaction completed\n\
+             [2019-07-14 21:35] [PACMAN] Running 'pacman -Syu'\n\
+             [2019-07-14 21:35] [ALPM] upgraded libev (4.25-1 -> 4.27-1)\n\
+             [2019-07-14 21:35] [ALPM] upgraded iso-codes (4.2-1 -> 4.3-1)"
+        )
+        .unwrap();
+
+        let transactions = from_file(&file.path().unwrap(), |_| {}).unwrap();
+
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(transactions[0].command, None);
+        assert_eq!(transactions[0].events.len(), 1);
+        assert_eq!(
+            transactions[1].command,
+            Some(String::from("pacman -Sy feh"))
+        );
+        assert_eq!(transactions[1].events.len(), 1);
+        assert_eq!(transactions[2].events.len(), 2);
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+}
@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::de::Error as DeError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::{Error, ErrorDetail};
+
+/// A package version in pacman's `[epoch:]pkgver[-pkgrel]` form.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Version {
+    pub epoch: Option<u32>,
+    pub pkgver: String,
+    pub pkgrel: Option<String>,
+}
+
+impl Version {
+    pub fn new(epoch: Option<u32>, pkgver: &str, pkgrel: Option<&str>) -> Version {
+        Version {
+            epoch,
+            pkgver: String::from(pkgver),
+            pkgrel: pkgrel.map(String::from),
+        }
+    }
+
+    /// Compares two versions like pacman's `vercmp`, the pkgrel is only compared if both versions have one.
+    pub fn vercmp(&self, other: &Version) -> Ordering {
+        self.epoch
+            .unwrap_or(0)
+            .cmp(&other.epoch.unwrap_or(0))
+            .then_with(|| rpmvercmp(&self.pkgver, &other.pkgver))
+            .then_with(|| match (&self.pkgrel, &other.pkgrel) {
+                (Some(a), Some(b)) => rpmvercmp(a, b),
+                _ => Ordering::Equal,
+            })
+    }
+}
+
+/*
+ * libalpm's rpmvercmp: alternating numeric and alphabetic segments are compared one by one,
+ * numbers by value, a remaining alphabetic segment (1.0alpha) is older than nothing (1.0).
+ */
+fn rpmvercmp(a: &str, b: &str) -> Ordering {
+    if a == b {
+        return Ordering::Equal;
+    }
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        let (sep_a, sep_b) = (i, j);
+        while i < a.len() && !a[i].is_ascii_alphanumeric() {
+            i += 1
+        }
+        while j < b.len() && !b[j].is_ascii_alphanumeric() {
+            j += 1
+        }
+        if i == a.len() || j == b.len() {
+            break;
+        }
+        // 1.0.1 is newer than 1.0a
+        if i - sep_a != j - sep_b {
+            return (i - sep_a).cmp(&(j - sep_b));
+        }
+        let is_num = a[i].is_ascii_digit();
+        let segment = |s: &[u8], start: usize| -> usize {
+            let mut end = start;
+            while end < s.len()
+                && (if is_num {
+                    s[end].is_ascii_digit()
+                } else {
+                    s[end].is_ascii_alphabetic()
+                })
+            {
+                end += 1
+            }
+            end
+        };
+        let (end_a, end_b) = (segment(a, i), segment(b, j));
+        // segments of different types, numbers are newer
+        if j == end_b {
+            return if is_num {
+                Ordering::Greater
+            } else {
+                Ordering::Less
+            };
+        }
+        let (mut seg_a, mut seg_b) = (&a[i..end_a], &b[j..end_b]);
+        if is_num {
+            while seg_a.len() > 1 && seg_a[0] == b'0' {
+                seg_a = &seg_a[1..]
+            }
+            while seg_b.len() > 1 && seg_b[0] == b'0' {
+                seg_b = &seg_b[1..]
+            }
+            if seg_a.len() != seg_b.len() {
+                return seg_a.len().cmp(&seg_b.len());
+            }
+        }
+        if seg_a != seg_b {
+            return seg_a.cmp(seg_b);
+        }
+        i = end_a;
+        j = end_b;
+    }
+    if i == a.len() && j == b.len() {
+        return Ordering::Equal;
+    }
+    if (i == a.len() && !b[j].is_ascii_alphabetic()) || (i < a.len() && a[i].is_ascii_alphabetic())
+    {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(epoch) = self.epoch {
+            write!(f, "{}:", epoch)?
+        }
+        write!(f, "{}", self.pkgver)?;
+        if let Some(pkgrel) = &self.pkgrel {
+            write!(f, "-{}", pkgrel)?
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(Error::new(ErrorDetail::InvalidVersion {
+                version: String::from(s),
+            }));
+        }
+        let (epoch, rest) = match s.split_once(':') {
+            Some((e, rest)) => match e.parse::<u32>() {
+                Ok(epoch) if epoch.to_string() == e => (Some(epoch), rest),
+                _ => (None, s),
+            },
+            None => (None, s),
+        };
+        let (pkgver, pkgrel) = match rest.rsplit_once('-') {
+            Some((pkgver, pkgrel)) if !pkgver.is_empty() => (pkgver, Some(pkgrel)),
+            _ => (rest, None),
+        };
+        Ok(Version::new(epoch, pkgver, pkgrel))
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Version {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_parse_version() {
+        let version: Version = "2:2019.1.2-1".parse().unwrap();
+        assert_eq!(version, Version::new(Some(2), "2019.1.2", Some("1")))
+    }
+
+    #[test]
+    fn should_parse_version_without_epoch_and_pkgrel() {
+        let version: Version = "430.26".parse().unwrap();
+        assert_eq!(version, Version::new(None, "430.26", None))
+    }
+
+    #[test]
+    fn should_print_version() {
+        for v in [
+            "2:2019.1.2-1",
+            "5.2.arch2-1",
+            "430.26",
+            "1.0-rc1-2",
+            "r12.abc:def-1",
+        ] {
+            let version: Version = v.parse().unwrap();
+            assert_eq!(version.to_string(), v)
+        }
+    }
+
+    #[test]
+    fn should_compare_versions() {
+        let vercmp = |a: &str, b: &str| {
+            a.parse::<Version>()
+                .unwrap()
+                .vercmp(&b.parse::<Version>().unwrap())
+        };
+        assert_eq!(vercmp("1.0-1", "1.0-1"), Ordering::Equal);
+        assert_eq!(vercmp("1.0-2", "1.0-1"), Ordering::Greater);
+        assert_eq!(vercmp("1.10-1", "1.9-1"), Ordering::Greater);
+        assert_eq!(vercmp("1:0.9-1", "2.0-1"), Ordering::Greater);
+        assert_eq!(vercmp("1.0a-1", "1.0-1"), Ordering::Less);
+        assert_eq!(vercmp("1.0alpha", "1.0beta"), Ordering::Less);
+        assert_eq!(vercmp("1.0.1", "1.0a"), Ordering::Greater);
+        assert_eq!(vercmp("1.0.a", "1.0.1"), Ordering::Less);
+        assert_eq!(vercmp("1.001", "1.1"), Ordering::Equal);
+        assert_eq!(vercmp("1.0", "1.0-5"), Ordering::Equal);
+        assert_eq!(vercmp("3.0.7-1", "3.0.8-1"), Ordering::Less);
+        assert_eq!(vercmp("1.0..1", "1.0.1"), Ordering::Greater)
+    }
+
+    #[test]
+    fn should_not_parse_empty_version() {
+        let version: Result<Version, Error> = "".parse();
+        assert!(version.is_err())
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::BuildHasher;
+use std::io::stdout;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{Config, Format};
+use crate::pacman::filter::Filter;
+use crate::pacman::PacmanEvent;
+use crate::pkghist::read_hosts;
+
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const GREENS: [u8; 5] = [237, 22, 28, 34, 46];
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Day {
+    date: String,
+    count: usize,
+}
+
+pub fn run(config: &Config) -> Result<(), Error> {
+    let mut days = BTreeMap::new();
+    for (_, pacman_events) in read_hosts(config)? {
+        let groups = pacman_events.filter_events(config);
+        for (date, count) in count_days(&groups) {
+            *days.entry(date).or_insert(0) += count
+        }
+    }
+    if days.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_activity(&mut stdout(), &days, &config.format)
+}
+
+fn count_days<S: BuildHasher>(
+    groups: &HashMap<&String, Vec<&PacmanEvent>, S>,
+) -> BTreeMap<NaiveDate, usize> {
+    let mut days = BTreeMap::new();
+    for event in groups.values().flatten() {
+        *days.entry(event.date.date()).or_insert(0) += 1
+    }
+    days
+}
+
+fn print_activity<W: std::io::Write>(
+    stdout: &mut W,
+    days: &BTreeMap<NaiveDate, usize>,
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        let days: Vec<Day> = days
+            .iter()
+            .map(|(date, count)| Day {
+                date: date.to_string(),
+                count: *count,
+            })
+            .collect();
+        writeln!(stdout, "{}", serde_json::to_string_pretty(&days).unwrap())?;
+        return Ok(());
+    }
+
+    let (first, last) = match (days.keys().next(), days.keys().next_back()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(()),
+    };
+    let max = *days.values().max().unwrap();
+    let with_colors = format.with_colors();
+
+    for year in first.year()..=last.year() {
+        let jan_1 = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let dec_31 = NaiveDate::from_ymd_opt(year, 12, 31).unwrap();
+        let start = jan_1 - Duration::days(jan_1.weekday().num_days_from_monday() as i64);
+        let weeks = ((dec_31 - start).num_days() / 7 + 1) as usize;
+
+        writeln!(stdout, "{}", year)?;
+        let mut months = String::new();
+        for week in 0..weeks {
+            let sunday = start + Duration::days(week as i64 * 7 + 6);
+            if months.chars().count() <= week && sunday.year() == year && sunday.day() <= 7 {
+                months.push_str(&sunday.format("%b").to_string())
+            } else if months.chars().count() <= week {
+                months.push(' ')
+            }
+        }
+        writeln!(stdout, "    {}", months.trim_end())?;
+
+        for (weekday, name) in WEEKDAYS.iter().enumerate() {
+            let mut row = String::new();
+            for week in 0..weeks {
+                let date = start + Duration::days((week * 7 + weekday) as i64);
+                if date.year() != year || date < first || date > last {
+                    row.push(' ');
+                    continue;
+                }
+                let level = level(*days.get(&date).unwrap_or(&0), max);
+                if with_colors {
+                    row.push_str(&format!(
+                        "{color}■{reset}",
+                        color = color::Fg(color::AnsiValue(GREENS[level])),
+                        reset = color::Fg(color::Reset)
+                    ))
+                } else {
+                    row.push(SHADES[level])
+                }
+            }
+            writeln!(stdout, "{} {}", name, row.trim_end())?
+        }
+        writeln!(stdout)?
+    }
+
+    if with_colors {
+        write!(stdout, "less ")?;
+        for green in GREENS.iter() {
+            write!(
+                stdout,
+                "{color}■{reset}",
+                color = color::Fg(color::AnsiValue(*green)),
+                reset = color::Fg(color::Reset)
+            )?
+        }
+        writeln!(stdout, " more")?
+    } else {
+        writeln!(stdout, "less {} more", SHADES.iter().collect::<String>())?
+    }
+
+    if let Some((from, to)) = longest_gap(days) {
+        writeln!(
+            stdout,
+            "longest time without changes: {} days ({} - {})",
+            (to - from).num_days(),
+            from,
+            to
+        )?
+    }
+    Ok(())
+}
+
+/*
+ * 0 for days without events, 1 to 4 relative to the busiest day
+ */
+fn level(count: usize, max: usize) -> usize {
+    if count == 0 {
+        0
+    } else {
+        ((count * 4 + max - 1) / max).clamp(1, 4)
+    }
+}
+
+fn longest_gap(days: &BTreeMap<NaiveDate, usize>) -> Option<(NaiveDate, NaiveDate)> {
+    days.keys()
+        .zip(days.keys().skip(1))
+        .max_by_key(|(from, to)| (**to - **from).num_days())
+        .map(|(from, to)| (*from, *to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn some_days() -> BTreeMap<NaiveDate, usize> {
+        let mut days = BTreeMap::new();
+        days.insert(NaiveDate::from_ymd_opt(2019, 1, 1).unwrap(), 4);
+        days.insert(NaiveDate::from_ymd_opt(2019, 1, 2).unwrap(), 1);
+        days.insert(NaiveDate::from_ymd_opt(2019, 1, 10).unwrap(), 2);
+        days
+    }
+
+    #[test]
+    fn should_count_events_per_day() {
+        let pacman_events: Vec<PacmanEvent> = vec![
+            "[2019-07-14 21:30] [ALPM] installed bash (5.0.0-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-14 22:31] [ALPM] installed linux (5.2.arch2-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-16 21:32] [ALPM] upgraded linux (5.2.arch2-1 -> 5.2.1.arch1-1)"
+                .parse()
+                .unwrap(),
+        ];
+        let days = count_days(&pacman_events.filter_packages(&Config::new()));
+        assert_eq!(days.len(), 2);
+        assert_eq!(
+            days.get(&NaiveDate::from_ymd_opt(2019, 7, 14).unwrap()),
+            Some(&2)
+        );
+        assert_eq!(
+            days.get(&NaiveDate::from_ymd_opt(2019, 7, 16).unwrap()),
+            Some(&1)
+        )
+    }
+
+    #[test]
+    fn should_calculate_level() {
+        assert_eq!(level(0, 10), 0);
+        assert_eq!(level(1, 10), 1);
+        assert_eq!(level(5, 10), 2);
+        assert_eq!(level(10, 10), 4)
+    }
+
+    #[test]
+    fn should_find_longest_gap() {
+        assert_eq!(
+            longest_gap(&some_days()),
+            Some((
+                NaiveDate::from_ymd_opt(2019, 1, 2).unwrap(),
+                NaiveDate::from_ymd_opt(2019, 1, 10).unwrap()
+            ))
+        )
+    }
+
+    #[test]
+    fn should_print_activity_json() {
+        let mut stdout = Vec::new();
+        print_activity(
+            &mut stdout,
+            &some_days(),
+            &Format::Json {
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "[\n  {\n    \"date\": \"2019-01-01\",\n    \"count\": 4\n  },\n  \
+             {\n    \"date\": \"2019-01-02\",\n    \"count\": 1\n  },\n  \
+             {\n    \"date\": \"2019-01-10\",\n    \"count\": 2\n  }\n]\n"
+        )
+    }
+
+    #[test]
+    fn should_print_activity_heatmap() {
+        let mut stdout = Vec::new();
+        print_activity(
+            &mut stdout,
+            &some_days(),
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "2019\n    Jan Feb Mar  Apr May Jun  Jul Aug Sep  Oct Nov Dec\n\
+             Mon  ·\nTue █·\nWed ░·\nThu ·▒\nFri ·\nSat ·\nSun ·\n\n\
+             less ·░▒▓█ more\n\
+             longest time without changes: 8 days (2019-01-02 - 2019-01-10)\n"
+        )
+    }
+}
@@ -0,0 +1,427 @@
+use std::cmp::Ordering;
+use std::fs;
+use std::io::stdout;
+
+use serde::{Deserialize, Serialize};
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{Config, Format};
+use crate::pacman::version::Version;
+use crate::pkghist::{all_histories, read_hosts, PackageHistory};
+
+/// A group of issues (AVG) of the Arch Linux security tracker, as exported to
+/// https://security.archlinux.org/issues/all.json
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Advisory {
+    pub name: String,
+    pub packages: Vec<String>,
+    pub status: String,
+    pub severity: String,
+    /// The version the tracker found vulnerable
+    #[serde(default)]
+    pub affected: Option<String>,
+    #[serde(default)]
+    pub fixed: Option<String>,
+    #[serde(default)]
+    pub issues: Vec<String>,
+}
+
+impl Advisory {
+    /*
+     * Without a fixed version the affected version and every later one stay exposed while the
+     * tracker says vulnerable, every version is unless the tracker says otherwise
+     */
+    fn is_vulnerable(&self, version: &Version) -> bool {
+        match (parse_version(&self.fixed), parse_version(&self.affected)) {
+            (Some(fixed), _) => version.vercmp(&fixed) == Ordering::Less,
+            (None, Some(affected)) if version.vercmp(&affected) != Ordering::Less => {
+                self.status == "Vulnerable"
+            }
+            (None, _) => self.status != "Not affected",
+        }
+    }
+
+    fn is_fixed_by(&self, version: &Version) -> bool {
+        parse_version(&self.fixed).is_some_and(|fixed| version.vercmp(&fixed) != Ordering::Less)
+    }
+}
+
+fn parse_version(version: &Option<String>) -> Option<Version> {
+    version.as_ref().and_then(|v| v.parse().ok())
+}
+
+/// The time a vulnerable version was installed on a host.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+struct Window {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+    version: String,
+    from: String,
+    /// `None` while the vulnerable version is still installed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<String>,
+    /// The version fixing the issue, `None` if the package was removed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fixed_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+struct Exposure {
+    cve: String,
+    group: String,
+    severity: String,
+    package: String,
+    windows: Vec<Window>,
+}
+
+pub fn run(config: &Config, file: &str) -> Result<(), Error> {
+    let advisories = load(file)?;
+    // past exposures count as well, whatever the dates or actions select and even if the package was removed since
+    let package_histories = all_histories(&read_hosts(config)?, config);
+    let exposures = exposures(&advisories, &package_histories);
+    if exposures.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_exposures(&mut stdout(), &exposures, &config.format)
+}
+
+pub fn load(file: &str) -> Result<Vec<Advisory>, Error> {
+    let content = fs::read_to_string(file).map_err(|e| {
+        Error::new(ErrorDetail::UnreadableLogfile {
+            path: String::from(file),
+            msg: e.to_string(),
+        })
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        Error::new(ErrorDetail::InvalidConfig {
+            msg: format!("{}: {}", file, e),
+        })
+    })
+}
+
+fn exposures(advisories: &[Advisory], package_histories: &[PackageHistory]) -> Vec<Exposure> {
+    let mut exposures = Vec::new();
+    for advisory in advisories.iter().filter(|a| a.status != "Not affected") {
+        for package_history in package_histories
+            .iter()
+            .filter(|p| advisory.packages.contains(&p.p))
+        {
+            let windows: Vec<Window> = package_history
+                .split_hosts()
+                .iter()
+                .flat_map(|(host, history)| windows(advisory, *host, history))
+                .collect();
+            if windows.is_empty() {
+                continue;
+            }
+            // the tracker groups issues fixed by the same version
+            let issues = if advisory.issues.is_empty() {
+                vec![advisory.name.clone()]
+            } else {
+                advisory.issues.clone()
+            };
+            for cve in issues {
+                exposures.push(Exposure {
+                    cve,
+                    group: advisory.name.clone(),
+                    severity: advisory.severity.clone(),
+                    package: package_history.p.clone(),
+                    windows: windows.clone(),
+                })
+            }
+        }
+    }
+    exposures.sort_by(|e1, e2| {
+        e1.cve
+            .cmp(&e2.cve)
+            .then_with(|| e1.package.cmp(&e2.package))
+    });
+    exposures
+}
+
+/*
+ * A window opens with the first event installing a vulnerable version and closes with the
+ * first event installing a fixed version or removing the package.
+ */
+fn windows(
+    advisory: &Advisory,
+    host: Option<&str>,
+    package_history: &PackageHistory,
+) -> Vec<Window> {
+    let mut windows = Vec::new();
+    let mut open: Option<Window> = None;
+    for event in &package_history.e {
+        let installed = event.action.is_installed();
+        let vulnerable = installed && advisory.is_vulnerable(&event.version);
+        let fixed = installed && advisory.is_fixed_by(&event.version);
+        match (open.take(), vulnerable) {
+            (None, true) => {
+                open = Some(Window {
+                    host: host.map(String::from),
+                    version: event.version.to_string(),
+                    from: event.printable_date(),
+                    to: None,
+                    fixed_by: None,
+                })
+            }
+            // only a removal or the tracker's fixed version close the window
+            (Some(window), _) if installed && !fixed => open = Some(window),
+            (Some(mut window), _) => {
+                window.to = Some(event.printable_date());
+                if fixed {
+                    window.fixed_by = Some(event.version.to_string())
+                }
+                windows.push(window)
+            }
+            (None, false) => {}
+        }
+    }
+    windows.extend(open);
+    windows
+}
+
+fn print_exposures<W: std::io::Write>(
+    stdout: &mut W,
+    exposures: &[Exposure],
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        writeln!(
+            stdout,
+            "{}",
+            serde_json::to_string_pretty(exposures).unwrap()
+        )?;
+        return Ok(());
+    }
+    let with_colors = format.with_colors();
+    for exposure in exposures {
+        writeln!(
+            stdout,
+            "{cve} ({group}, {severity}) {package}",
+            cve = exposure.cve,
+            group = exposure.group,
+            severity = exposure.severity,
+            package = exposure.package
+        )?;
+        for window in &exposure.windows {
+            if with_colors && window.to.is_none() {
+                write!(stdout, "{red}", red = color::Fg(color::Red))?
+            } else if with_colors {
+                write!(stdout, "{green}", green = color::Fg(color::Green))?
+            }
+            let end = match (&window.to, &window.fixed_by) {
+                (Some(to), Some(fixed_by)) => format!("{} fixed by {}", to, fixed_by),
+                (Some(to), None) => format!("{} removed", to),
+                _ => String::from("still exposed"),
+            };
+            write!(
+                stdout,
+                "  {version}: {from} - {end}",
+                version = window.version,
+                from = window.from,
+                end = end
+            )?;
+            if let Some(host) = &window.host {
+                write!(stdout, " on {}", host)?
+            }
+            if with_colors {
+                writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+            } else {
+                writeln!(stdout)?
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write;
+
+    use filepath::FilePath;
+
+    use crate::pacman::action::Action;
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    fn advisory(fixed: Option<&str>) -> Advisory {
+        Advisory {
+            name: String::from("AVG-2843"),
+            packages: vec![String::from("openssl")],
+            status: String::from(if fixed.is_some() {
+                "Fixed"
+            } else {
+                "Vulnerable"
+            }),
+            severity: String::from("High"),
+            affected: Some(String::from("3.0.7-1")),
+            fixed: fixed.map(String::from),
+            issues: vec![String::from("CVE-2023-0286"), String::from("CVE-2023-0215")],
+        }
+    }
+
+    fn some_package_histories() -> Vec<PackageHistory> {
+        vec![PackageHistory {
+            p: String::from("openssl"),
+            e: vec![
+                event("3.0.7-1", "2023-01-10 12:00:00", Action::Installed),
+                event("3.0.7-2", "2023-01-20 12:00:00", Action::Upgraded),
+                event("3.0.8-1", "2023-02-08 09:00:00", Action::Upgraded),
+                event("3.0.7-2", "2023-03-01 10:00:00", Action::Downgraded),
+            ],
+            lifetime: None,
+        }]
+    }
+
+    #[test]
+    fn should_load_advisories() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "[{{\"name\": \"AVG-2843\", \"packages\": [\"openssl\"], \"status\": \"Fixed\", \
+             \"severity\": \"High\", \"type\": \"arbitrary code execution\", \"affected\": \"3.0.7-1\", \
+             \"fixed\": \"3.0.8-1\", \"ticket\": null, \"issues\": [\"CVE-2023-0286\", \"CVE-2023-0215\"], \
+             \"advisories\": [\"ASA-202302-07\"]}}]"
+        )
+        .unwrap();
+
+        let advisories = load(&file_name).unwrap();
+
+        assert_eq!(advisories, vec![advisory(Some("3.0.8-1"))]);
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_not_load_invalid_advisories() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "{{\"name\": \"AVG-2843\"}}").unwrap();
+
+        let result = load(&file_name);
+
+        assert_eq!(result.unwrap_err().exit_code(), 3);
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_find_exposure_windows() {
+        let exposures = exposures(&[advisory(Some("3.0.8-1"))], &some_package_histories());
+
+        assert_eq!(exposures.len(), 2);
+        assert_eq!(exposures[0].cve, "CVE-2023-0215");
+        assert_eq!(exposures[1].cve, "CVE-2023-0286");
+        assert_eq!(
+            exposures[0].windows,
+            vec![
+                Window {
+                    host: None,
+                    version: String::from("3.0.7-1"),
+                    from: String::from("2023-01-10 12:00:00"),
+                    to: Some(String::from("2023-02-08 09:00:00")),
+                    fixed_by: Some(String::from("3.0.8-1")),
+                },
+                Window {
+                    host: None,
+                    version: String::from("3.0.7-2"),
+                    from: String::from("2023-03-01 10:00:00"),
+                    to: None,
+                    fixed_by: None,
+                }
+            ]
+        )
+    }
+
+    #[test]
+    fn should_close_window_on_removal() {
+        let package_histories = vec![PackageHistory {
+            p: String::from("openssl"),
+            e: vec![
+                event("3.0.7-1", "2023-01-10 12:00:00", Action::Installed),
+                event("3.0.7-1", "2023-01-20 12:00:00", Action::Removed),
+            ],
+            lifetime: None,
+        }];
+
+        let exposures = exposures(&[advisory(None)], &package_histories);
+
+        assert_eq!(
+            exposures[0].windows[0].to,
+            Some(String::from("2023-01-20 12:00:00"))
+        );
+        assert_eq!(exposures[0].windows[0].fixed_by, None)
+    }
+
+    #[test]
+    fn should_keep_versions_newer_than_affected_exposed_without_fix() {
+        let exposures = exposures(&[advisory(None)], &some_package_histories());
+
+        assert_eq!(
+            exposures[0].windows,
+            vec![Window {
+                host: None,
+                version: String::from("3.0.7-1"),
+                from: String::from("2023-01-10 12:00:00"),
+                to: None,
+                fixed_by: None,
+            }]
+        )
+    }
+
+    #[test]
+    fn should_not_close_window_with_newer_unfixed_version() {
+        let mut advisory = advisory(None);
+        advisory.status = String::from("Unknown");
+        let package_histories = vec![PackageHistory {
+            p: String::from("openssl"),
+            e: vec![
+                event("3.0.6-1", "2023-01-05 12:00:00", Action::Installed),
+                event("3.0.7-2", "2023-01-20 12:00:00", Action::Upgraded),
+            ],
+            lifetime: None,
+        }];
+
+        let exposures = exposures(&[advisory], &package_histories);
+
+        assert_eq!(exposures[0].windows.len(), 1);
+        assert_eq!(exposures[0].windows[0].to, None)
+    }
+
+    #[test]
+    fn should_ignore_fixed_versions() {
+        let package_histories = vec![PackageHistory {
+            p: String::from("openssl"),
+            e: vec![event("3.0.8-1", "2023-02-08 09:00:00", Action::Installed)],
+            lifetime: None,
+        }];
+
+        let exposures = exposures(&[advisory(Some("3.0.8-1"))], &package_histories);
+
+        assert!(exposures.is_empty())
+    }
+
+    #[test]
+    fn should_print_exposures_no_colors() {
+        let exposures = exposures(&[advisory(Some("3.0.8-1"))], &some_package_histories());
+        let mut stdout = Vec::new();
+        print_exposures(
+            &mut stdout,
+            &exposures[..1],
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "CVE-2023-0215 (AVG-2843, High) openssl\n  \
+             3.0.7-1: 2023-01-10 12:00:00 - 2023-02-08 09:00:00 fixed by 3.0.8-1\n  \
+             3.0.7-2: 2023-03-01 10:00:00 - still exposed\n"
+        )
+    }
+}
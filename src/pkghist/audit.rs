@@ -0,0 +1,534 @@
+use std::io::stdout;
+
+use chrono::{Duration, NaiveDateTime};
+use serde::Serialize;
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{Config, Format};
+use crate::pacman::action::Action;
+use crate::pacman::filter::matches_filters;
+use crate::pacman::group::Group;
+use crate::pacman::transaction;
+use crate::pacman::transaction::Transaction;
+use crate::pacman::PacmanEvent;
+use crate::source::Source;
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Warning {
+    PartialUpgrade {
+        date: String,
+        command: String,
+        synced: String,
+        packages: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+    },
+    Churn {
+        package: String,
+        from: String,
+        to: String,
+        flips: usize,
+        events: Vec<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        host: Option<String>,
+    },
+}
+
+/// How often a package has to flip-flop to be reported as churning.
+const MIN_FLIPS: usize = 3;
+
+pub fn run(config: &Config, churn_window: u32) -> Result<(), Error> {
+    let mut warnings = Vec::new();
+    for (host, logfile) in config.logfiles() {
+        // transactions are only logged by pacman
+        if config.source == Source::Pacman {
+            let transactions = transaction::from_logfile(logfile, config.on_skipped_line)?;
+            warnings.extend(partial_upgrades(&transactions, &config.after, host))
+        }
+        let pacman_events = config
+            .source
+            .log_source()
+            .read_logfile(logfile, config.on_skipped_line)?;
+        warnings.extend(churn(
+            &pacman_events,
+            Duration::days(i64::from(churn_window)),
+            &config.after,
+            host,
+        ))
+    }
+    let warnings = filter_warnings(warnings, config);
+    if warnings.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_warnings(&mut stdout(), &warnings, &config.format)
+}
+
+/*
+ * Partial upgrades keep the packages passing the filters, and are dropped without any.
+ */
+fn filter_warnings(warnings: Vec<Warning>, config: &Config) -> Vec<Warning> {
+    warnings
+        .into_iter()
+        .filter_map(|warning| match warning {
+            Warning::PartialUpgrade {
+                date,
+                command,
+                synced,
+                packages,
+                host,
+            } => {
+                let packages: Vec<String> = packages
+                    .into_iter()
+                    .filter(|p| matches_filters(p, config))
+                    .collect();
+                if packages.is_empty() {
+                    None
+                } else {
+                    Some(Warning::PartialUpgrade {
+                        date,
+                        command,
+                        synced,
+                        packages,
+                        host,
+                    })
+                }
+            }
+            Warning::Churn { ref package, .. } if !matches_filters(package, config) => None,
+            churn => Some(churn),
+        })
+        .collect()
+}
+
+/*
+ * A sync with refresh (-Sy) but without sysupgrade (-u) leaves the databases
+ * newer than the installed packages. Every transaction installing or upgrading
+ * packages before the next full upgrade (-Su) is a partial upgrade.
+ */
+fn partial_upgrades(
+    transactions: &[Transaction],
+    after: &Option<NaiveDateTime>,
+    host: Option<&str>,
+) -> Vec<Warning> {
+    let mut synced: Option<&Transaction> = None;
+    let mut warnings = Vec::new();
+    for transaction in transactions {
+        let operation = transaction.operation();
+        if operation.sync && operation.sysupgrade {
+            synced = None;
+            continue;
+        }
+        if operation.sync && operation.refresh {
+            synced = Some(transaction);
+        }
+        if let Some(s) = synced {
+            let packages: Vec<String> = transaction
+                .events
+                .iter()
+                .filter(|e| e.action == Action::Installed || e.action == Action::Upgraded)
+                .map(|e| e.package.clone())
+                .collect();
+            let is_after = match after {
+                Some(a) => transaction.date > *a,
+                None => true,
+            };
+            if !packages.is_empty() && is_after {
+                warnings.push(Warning::PartialUpgrade {
+                    date: transaction.date.to_string(),
+                    command: transaction.command.clone().unwrap_or_default(),
+                    synced: s.date.to_string(),
+                    packages,
+                    host: host.map(String::from),
+                })
+            }
+        }
+    }
+    warnings
+}
+
+/*
+ * A flip reverts the previous event: removing a package, installing it again after a removal,
+ * or changing the direction between up- and downgrades.
+ */
+fn is_flip(previous: &Action, current: &Action) -> bool {
+    matches!(
+        (previous, current),
+        (Action::Removed, Action::Installed)
+            | (Action::Removed, Action::Reinstalled)
+            | (Action::Installed, Action::Removed)
+            | (Action::Reinstalled, Action::Removed)
+            | (Action::Upgraded, Action::Removed)
+            | (Action::Downgraded, Action::Removed)
+            | (Action::Upgraded, Action::Downgraded)
+            | (Action::Downgraded, Action::Upgraded)
+    )
+}
+
+/*
+ * A package churns if it flips at least MIN_FLIPS times, each flip at most `window` after the previous one.
+ */
+fn churn(
+    pacman_events: &Vec<PacmanEvent>,
+    window: Duration,
+    after: &Option<NaiveDateTime>,
+    host: Option<&str>,
+) -> Vec<Warning> {
+    let groups = pacman_events.group();
+    let mut packages: Vec<&&String> = groups.keys().collect();
+    packages.sort();
+    let mut warnings = Vec::new();
+    for package in packages {
+        let mut events = groups[*package].clone();
+        events.sort();
+        let flips: Vec<usize> = (1..events.len())
+            .filter(|&idx| is_flip(&events[idx - 1].action, &events[idx].action))
+            .collect();
+        let mut start = 0;
+        for end in 0..flips.len() {
+            let is_last = end + 1 == flips.len()
+                || events[flips[end + 1]].date - events[flips[end]].date > window;
+            if !is_last {
+                continue;
+            }
+            let is_after = match after {
+                Some(a) => events[flips[end]].date > *a,
+                None => true,
+            };
+            if end + 1 - start >= MIN_FLIPS && is_after {
+                // the event before the first flip shows what was reverted
+                let churning = &events[flips[start] - 1..=flips[end]];
+                warnings.push(Warning::Churn {
+                    package: String::from(*package),
+                    from: churning[0].date.to_string(),
+                    to: churning[churning.len() - 1].date.to_string(),
+                    flips: end + 1 - start,
+                    events: churning
+                        .iter()
+                        .map(|e| format!("[{}] {} {}", e.date, e.action, e.printable_version()))
+                        .collect(),
+                    host: host.map(String::from),
+                })
+            }
+            start = end + 1
+        }
+    }
+    warnings
+}
+
+fn print_warnings<W: std::io::Write>(
+    stdout: &mut W,
+    warnings: &[Warning],
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        writeln!(
+            stdout,
+            "{}",
+            serde_json::to_string_pretty(warnings).unwrap()
+        )?;
+        return Ok(());
+    }
+    let with_colors = format.with_colors();
+    for warning in warnings {
+        match warning {
+            Warning::PartialUpgrade {
+                date,
+                command,
+                synced,
+                packages,
+                host,
+            } => {
+                if with_colors {
+                    write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
+                }
+                write!(stdout, "[{date}] partial upgrade by '{command}'")?;
+                if let Some(host) = host {
+                    write!(stdout, " on {host}")?
+                }
+                if with_colors {
+                    writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+                } else {
+                    writeln!(stdout)?
+                }
+                writeln!(
+                    stdout,
+                    "  databases synced at {synced} without a full system upgrade"
+                )?;
+                for package in packages {
+                    writeln!(stdout, "    {package}")?
+                }
+            }
+            Warning::Churn {
+                package,
+                from,
+                to,
+                flips,
+                events,
+                host,
+            } => {
+                if with_colors {
+                    write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
+                }
+                write!(
+                    stdout,
+                    "[{from} - {to}] {package} flip-flopped {flips} times"
+                )?;
+                if let Some(host) = host {
+                    write!(stdout, " on {host}")?
+                }
+                if with_colors {
+                    writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+                } else {
+                    writeln!(stdout)?
+                }
+                for event in events {
+                    writeln!(stdout, "    {event}")?
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use crate::pacman::PacmanEvent;
+
+    use super::*;
+
+    fn transaction(minute: u32, command: &str, events: &[&str]) -> Transaction {
+        let mut transaction = Transaction::new(
+            NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2019, 7, 14).unwrap(),
+                NaiveTime::from_hms_opt(21, minute, 0).unwrap(),
+            ),
+            Some(String::from(command)),
+        );
+        transaction.events = events
+            .iter()
+            .map(|e| e.parse::<PacmanEvent>().unwrap())
+            .collect();
+        transaction
+    }
+
+    #[test]
+    fn should_detect_partial_upgrade() {
+        let transactions = vec![
+            transaction(
+                30,
+                "pacman -Sy feh",
+                &["[2019-07-14 21:30] [ALPM] upgraded feh (3.1.3-1 -> 3.2-1)"],
+            ),
+            transaction(
+                31,
+                "pacman -S libev",
+                &["[2019-07-14 21:31] [ALPM] upgraded libev (4.25-1 -> 4.27-1)"],
+            ),
+            transaction(
+                32,
+                "pacman -Syu",
+                &["[2019-07-14 21:32] [ALPM] upgraded bash (5.0.0-1 -> 5.0.2-1)"],
+            ),
+            transaction(
+                33,
+                "pacman -S vim",
+                &["[2019-07-14 21:33] [ALPM] installed vim (8.1-1)"],
+            ),
+        ];
+
+        let warnings = partial_upgrades(&transactions, &None, None);
+
+        assert_eq!(
+            warnings,
+            vec![
+                Warning::PartialUpgrade {
+                    date: String::from("2019-07-14 21:30:00"),
+                    command: String::from("pacman -Sy feh"),
+                    synced: String::from("2019-07-14 21:30:00"),
+                    packages: vec![String::from("feh")],
+                    host: None,
+                },
+                Warning::PartialUpgrade {
+                    date: String::from("2019-07-14 21:31:00"),
+                    command: String::from("pacman -S libev"),
+                    synced: String::from("2019-07-14 21:30:00"),
+                    packages: vec![String::from("libev")],
+                    host: None,
+                }
+            ]
+        )
+    }
+
+    #[test]
+    fn should_not_detect_removals_as_partial_upgrade() {
+        let transactions = vec![
+            transaction(30, "pacman -Sy", &[]),
+            transaction(
+                31,
+                "pacman -Rs feh",
+                &["[2019-07-14 21:31] [ALPM] removed feh (3.2-1)"],
+            ),
+        ];
+
+        let warnings = partial_upgrades(&transactions, &None, None);
+
+        assert!(warnings.is_empty())
+    }
+
+    #[test]
+    fn should_filter_warnings() {
+        let warnings = vec![
+            Warning::PartialUpgrade {
+                date: String::from("2019-07-14 21:30:00"),
+                command: String::from("pacman -Sy feh vim"),
+                synced: String::from("2019-07-14 21:30:00"),
+                packages: vec![String::from("feh"), String::from("vim")],
+                host: None,
+            },
+            Warning::PartialUpgrade {
+                date: String::from("2019-07-14 21:31:00"),
+                command: String::from("pacman -S vim"),
+                synced: String::from("2019-07-14 21:30:00"),
+                packages: vec![String::from("vim")],
+                host: None,
+            },
+            Warning::Churn {
+                package: String::from("vim"),
+                from: String::from("2019-07-01 10:00:00"),
+                to: String::from("2019-07-13 10:00:00"),
+                flips: 3,
+                events: Vec::new(),
+                host: None,
+            },
+        ];
+        let mut config = Config::new();
+        config.filters = vec![regex::Regex::new("^vim$").unwrap()];
+        config.exclude = true;
+
+        let filtered = filter_warnings(warnings, &config);
+
+        assert_eq!(
+            filtered,
+            vec![Warning::PartialUpgrade {
+                date: String::from("2019-07-14 21:30:00"),
+                command: String::from("pacman -Sy feh vim"),
+                synced: String::from("2019-07-14 21:30:00"),
+                packages: vec![String::from("feh")],
+                host: None,
+            }]
+        )
+    }
+
+    fn pacman_events(lines: &[&str]) -> Vec<PacmanEvent> {
+        lines.iter().map(|l| l.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn should_detect_churn() {
+        let pacman_events = pacman_events(&[
+            "[2019-07-01 10:00] [ALPM] installed feh (3.1-1)",
+            "[2019-07-10 10:00] [ALPM] installed vim (8.1-1)",
+            "[2019-07-10 10:00] [ALPM] removed feh (3.1-1)",
+            "[2019-07-12 10:00] [ALPM] installed feh (3.1-1)",
+            "[2019-07-13 10:00] [ALPM] removed feh (3.1-1)",
+            "[2019-07-13 11:00] [ALPM] upgraded vim (8.1-1 -> 8.2-1)",
+            "[2019-07-14 11:00] [ALPM] downgraded vim (8.2-1 -> 8.1-1)",
+            "[2019-07-15 11:00] [ALPM] upgraded vim (8.1-1 -> 8.2-1)",
+        ]);
+
+        let warnings = churn(&pacman_events, Duration::days(7), &None, None);
+
+        // vim was only downgraded and upgraded again, which is a single rollback
+        assert_eq!(
+            warnings,
+            vec![Warning::Churn {
+                package: String::from("feh"),
+                from: String::from("2019-07-01 10:00:00"),
+                to: String::from("2019-07-13 10:00:00"),
+                flips: 3,
+                events: vec![
+                    String::from("[2019-07-01 10:00:00] Installed 3.1-1"),
+                    String::from("[2019-07-10 10:00:00] Removed 3.1-1"),
+                    String::from("[2019-07-12 10:00:00] Installed 3.1-1"),
+                    String::from("[2019-07-13 10:00:00] Removed 3.1-1"),
+                ],
+                host: None,
+            }]
+        )
+    }
+
+    #[test]
+    fn should_not_detect_churn_outside_of_window() {
+        let pacman_events = pacman_events(&[
+            "[2019-07-01 10:00] [ALPM] upgraded vim (8.1-1 -> 8.2-1)",
+            "[2019-07-02 10:00] [ALPM] downgraded vim (8.2-1 -> 8.1-1)",
+            "[2019-07-03 10:00] [ALPM] upgraded vim (8.1-1 -> 8.2-1)",
+            "[2019-08-03 10:00] [ALPM] downgraded vim (8.2-1 -> 8.1-1)",
+        ]);
+
+        assert!(churn(&pacman_events, Duration::days(7), &None, None).is_empty());
+        assert_eq!(
+            churn(&pacman_events, Duration::days(31), &None, None).len(),
+            1
+        )
+    }
+
+    #[test]
+    fn should_print_churn_no_colors() {
+        let warnings = vec![Warning::Churn {
+            package: String::from("feh"),
+            from: String::from("2019-07-01 10:00:00"),
+            to: String::from("2019-07-03 10:00:00"),
+            flips: 3,
+            events: vec![String::from("[2019-07-01 10:00:00] Installed 3.1-1")],
+            host: None,
+        }];
+        let mut stdout = Vec::new();
+        print_warnings(
+            &mut stdout,
+            &warnings,
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "[2019-07-01 10:00:00 - 2019-07-03 10:00:00] feh flip-flopped 3 times\n    \
+             [2019-07-01 10:00:00] Installed 3.1-1\n"
+        )
+    }
+
+    #[test]
+    fn should_print_warnings_no_colors() {
+        let warnings = vec![Warning::PartialUpgrade {
+            date: String::from("2019-07-14 21:30:00"),
+            command: String::from("pacman -Sy feh"),
+            synced: String::from("2019-07-14 21:30:00"),
+            packages: vec![String::from("feh")],
+            host: Some(String::from("web1")),
+        }];
+        let mut stdout = Vec::new();
+        print_warnings(
+            &mut stdout,
+            &warnings,
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "[2019-07-14 21:30:00] partial upgrade by 'pacman -Sy feh' on web1\n  \
+             databases synced at 2019-07-14 21:30:00 without a full system upgrade\n    \
+             feh\n"
+        )
+    }
+}
@@ -0,0 +1,246 @@
+use std::io::stdout;
+
+use serde::Serialize;
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{Config, Format};
+use crate::pkghist::{all_histories, read_hosts, PackageHistory};
+
+/// The version of a package currently installed on some hosts.
+#[derive(Debug, Serialize, PartialEq)]
+struct Rollout {
+    v: String,
+    h: Vec<String>,
+}
+
+/// The versions of a package installed across the fleet, the most recently installed version first.
+#[derive(Debug, Serialize, PartialEq)]
+struct Package {
+    p: String,
+    v: Vec<Rollout>,
+}
+
+pub fn run(config: &Config) -> Result<(), Error> {
+    if config.hosts.is_empty() {
+        return Err(Error::new(ErrorDetail::InvalidConfig {
+            msg: String::from("The fleet report needs at least one --host"),
+        }));
+    }
+    // the current versions are taken from the latest events, whatever --first, --limit or --action select
+    let package_histories = all_histories(&read_hosts(config)?, config);
+    let packages = fleet(&package_histories);
+    if packages.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_fleet(&mut stdout(), &packages, &config.format)
+}
+
+fn fleet(package_histories: &[PackageHistory]) -> Vec<Package> {
+    let mut packages: Vec<Package> = package_histories
+        .iter()
+        .filter_map(|package_history| {
+            let mut latest: Vec<_> = package_history
+                .split_hosts()
+                .into_iter()
+                .filter_map(|(host, history)| {
+                    let event = history.e.last()?.clone();
+                    match (host, event.action.is_removed()) {
+                        (Some(host), false) => Some((String::from(host), event)),
+                        _ => None,
+                    }
+                })
+                .collect();
+            latest.sort_by(|(h1, e1), (h2, e2)| e2.date.cmp(&e1.date).then_with(|| h1.cmp(h2)));
+
+            let mut rollouts: Vec<Rollout> = Vec::new();
+            for (host, event) in latest {
+                let version = event.version.to_string();
+                match rollouts.iter_mut().find(|r| r.v == version) {
+                    Some(rollout) => rollout.h.push(host),
+                    None => rollouts.push(Rollout {
+                        v: version,
+                        h: vec![host],
+                    }),
+                }
+            }
+            for rollout in rollouts.iter_mut() {
+                rollout.h.sort()
+            }
+            if rollouts.is_empty() {
+                None
+            } else {
+                Some(Package {
+                    p: package_history.p.clone(),
+                    v: rollouts,
+                })
+            }
+        })
+        .collect();
+    packages.sort_by(|p1, p2| p1.p.cmp(&p2.p));
+    packages
+}
+
+fn print_fleet<W: std::io::Write>(
+    stdout: &mut W,
+    packages: &[Package],
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        writeln!(
+            stdout,
+            "{}",
+            serde_json::to_string_pretty(packages).unwrap()
+        )?;
+        return Ok(());
+    }
+    let with_colors = format.with_colors();
+    let v_max = packages
+        .iter()
+        .flat_map(|p| &p.v)
+        .map(|r| r.v.len())
+        .max()
+        .unwrap_or(0);
+    for package in packages {
+        // packages running different versions on different hosts stand out
+        if with_colors && package.v.len() > 1 {
+            writeln!(
+                stdout,
+                "{yellow}{package}{reset}",
+                yellow = color::Fg(color::Yellow),
+                package = package.p,
+                reset = color::Fg(color::Reset)
+            )?
+        } else if with_colors {
+            writeln!(
+                stdout,
+                "{green}{package}{reset}",
+                green = color::Fg(color::Green),
+                package = package.p,
+                reset = color::Fg(color::Reset)
+            )?
+        } else {
+            writeln!(stdout, "{}", package.p)?
+        }
+        for rollout in &package.v {
+            writeln!(
+                stdout,
+                "  {version: <v_max$}  {hosts}",
+                version = rollout.v,
+                v_max = v_max,
+                hosts = rollout.h.join(", ")
+            )?
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pacman::action::Action;
+    use crate::pkghist::tests::event;
+    use crate::pkghist::Event;
+
+    use super::*;
+
+    fn on(host: &str, mut event: Event) -> Event {
+        event.host = Some(String::from(host));
+        event
+    }
+
+    fn some_package_histories() -> Vec<PackageHistory> {
+        vec![
+            PackageHistory {
+                p: String::from("linux"),
+                e: vec![
+                    on(
+                        "web1",
+                        event("6.6.9-1", "2024-01-10 12:00:00", Action::Installed),
+                    ),
+                    on(
+                        "db1",
+                        event("6.6.9-1", "2024-01-10 13:00:00", Action::Installed),
+                    ),
+                    on(
+                        "web2",
+                        event("6.7.1-1", "2024-01-11 12:00:00", Action::Installed),
+                    ),
+                    on(
+                        "web1",
+                        event("6.7.1-1", "2024-01-12 12:00:00", Action::Upgraded),
+                    ),
+                ],
+                lifetime: None,
+            },
+            PackageHistory {
+                p: String::from("feh"),
+                e: vec![
+                    on(
+                        "web1",
+                        event("3.10-1", "2024-01-10 12:00:00", Action::Installed),
+                    ),
+                    on(
+                        "web1",
+                        event("3.10-1", "2024-01-11 12:00:00", Action::Removed),
+                    ),
+                ],
+                lifetime: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn should_collect_current_versions() {
+        let packages = fleet(&some_package_histories());
+        assert_eq!(
+            packages,
+            vec![Package {
+                p: String::from("linux"),
+                v: vec![
+                    Rollout {
+                        v: String::from("6.7.1-1"),
+                        h: vec![String::from("web1"), String::from("web2")]
+                    },
+                    Rollout {
+                        v: String::from("6.6.9-1"),
+                        h: vec![String::from("db1")]
+                    }
+                ]
+            }]
+        )
+    }
+
+    #[test]
+    fn should_print_fleet_no_colors() {
+        let packages = fleet(&some_package_histories());
+        let mut stdout = Vec::new();
+        print_fleet(
+            &mut stdout,
+            &packages,
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(str, "linux\n  6.7.1-1  web1, web2\n  6.6.9-1  db1\n")
+    }
+
+    #[test]
+    fn should_print_fleet_json() {
+        let packages = fleet(&some_package_histories());
+        let mut stdout = Vec::new();
+        print_fleet(
+            &mut stdout,
+            &packages,
+            &Format::Json {
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&stdout).unwrap();
+        assert_eq!(json[0]["p"], "linux");
+        assert_eq!(json[0]["v"][1]["h"][0], "db1")
+    }
+}
@@ -1,10 +1,15 @@
+use chrono::Duration;
 use serde_json;
 
 use crate::error::Error;
-use crate::opt::Format;
+use crate::opt::{Column, Format, Palette};
 use crate::pacman::action::Action;
+use crate::pkghist::log::format_log;
+use crate::pkghist::report::{format_html, format_markdown};
+use crate::pkghist::template::format_template;
+use crate::pkghist::timeline::format_timeline;
 use crate::pkghist::{Event, PackageHistory};
-use termion::color;
+use termion::{color, terminal_size};
 
 fn format_json<W: std::io::Write>(
     stdout: &mut W,
@@ -26,13 +31,14 @@ fn format_plain<W: std::io::Write>(
     package_histories: &[PackageHistory],
     with_colors: bool,
     without_details: bool,
+    palette: &Palette,
 ) -> Result<(), Error> {
     for package_history in package_histories {
         if with_colors {
             // check if last event is a removal
-            match last_action(&package_history) {
-                Action::Removed => write!(stdout, "{red}", red = color::Fg(color::Red))?,
-                _ => write!(stdout, "{green}", green = color::Fg(color::Green))?,
+            match last_action(package_history) {
+                Action::Removed => write!(stdout, "{}", action_color(palette, &Action::Removed))?,
+                _ => write!(stdout, "{}", action_color(palette, &Action::Installed))?,
             }
             writeln!(
                 stdout,
@@ -46,33 +52,41 @@ fn format_plain<W: std::io::Write>(
         if !without_details {
             for event in &package_history.e {
                 if with_colors {
-                    match event.a.parse().unwrap() {
-                        Action::Removed => write!(stdout, "{red}", red = color::Fg(color::Red))?,
-                        Action::Downgraded => {
-                            write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
+                    // only removals and downgrades stand out, unless the palette colors the action
+                    match event.action {
+                        Action::Removed | Action::Downgraded => {
+                            write!(stdout, "{}", action_color(palette, &event.action))?
+                        }
+                        _ => {
+                            if let Some(c) = palette.color(&event.action) {
+                                write!(stdout, "{}", color::Fg(c))?
+                            }
                         }
-                        _ => {}
                     }
                     writeln!(
                         stdout,
-                        "  [{date}] {action}",
-                        date = event.d,
-                        action = event.a,
+                        "  [{date}] {action}{host}{boot}",
+                        date = event.printable_date(),
+                        action = event.action,
+                        host = on_host(event),
+                        boot = on_boot(event),
                     )?;
                     writeln!(
                         stdout,
                         "    {version}{reset}",
-                        version = event.v,
+                        version = event.version,
                         reset = color::Fg(color::Reset)
                     )?
                 } else {
                     writeln!(
                         stdout,
-                        "  [{date}] {action}",
-                        date = event.d,
-                        action = event.a
+                        "  [{date}] {action}{host}{boot}",
+                        date = event.printable_date(),
+                        action = event.action,
+                        host = on_host(event),
+                        boot = on_boot(event),
                     )?;
-                    writeln!(stdout, "    {version}", version = event.v)?
+                    writeln!(stdout, "    {version}", version = event.version)?
                 }
             }
         }
@@ -80,88 +94,190 @@ fn format_plain<W: std::io::Write>(
     Ok(())
 }
 
+/*
+ * The columns in their order, `--no-details` only prints the package names. The host column is
+ * only printed if the events were read using --host, the duration column only using --durations.
+ */
+fn compact_columns(
+    package_histories: &[PackageHistory],
+    columns: &[Column],
+    without_details: bool,
+) -> Vec<Column> {
+    let events = || package_histories.iter().flat_map(|p| &p.e);
+    let mut defaults = vec![Column::Package];
+    if events().any(|e| e.host.is_some()) {
+        defaults.push(Column::Host)
+    }
+    if without_details {
+        return defaults;
+    }
+    if !columns.is_empty() {
+        return columns.to_vec();
+    }
+    defaults.extend([Column::Date, Column::Action, Column::Version]);
+    if events().any(|e| e.duration.is_some()) {
+        defaults.push(Column::Duration)
+    }
+    defaults
+}
+
+/*
+ * From and To are only set for up- and downgrades, Version is the version after every event
+ */
+fn cell(column: &Column, package: &str, event: &Event) -> String {
+    match column {
+        Column::Package => String::from(package),
+        Column::Host => event.host.clone().unwrap_or_default(),
+        Column::Date => event.printable_date(),
+        Column::Action => event.action.to_string(),
+        Column::Version => event.version.to_string(),
+        Column::To => event
+            .from
+            .as_ref()
+            .map(|_| event.version.to_string())
+            .unwrap_or_default(),
+        Column::From => event
+            .from
+            .as_ref()
+            .map(|f| f.to_string())
+            .unwrap_or_default(),
+        Column::Duration => event.duration.map(printable_duration).unwrap_or_default(),
+    }
+}
+
+/*
+ * The widest columns are shrunk until the table fits into the terminal, but never below 4 characters
+ */
+fn column_widths(rows: &[Vec<String>], width: Option<usize>) -> Vec<usize> {
+    let mut widths: Vec<usize> = match rows.first() {
+        Some(row) => vec![0; row.len()],
+        None => return Vec::new(),
+    };
+    for row in rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count())
+        }
+    }
+    if let Some(width) = width {
+        // the borders take a character before every column and one at the end
+        let available = width.saturating_sub(widths.len() + 1);
+        while widths.iter().sum::<usize>() > available {
+            match widths.iter_mut().filter(|w| **w > 4).max() {
+                Some(widest) => *widest -= 1,
+                None => break,
+            }
+        }
+    }
+    widths
+}
+
+fn truncate(cell: &str, width: usize) -> String {
+    if cell.chars().count() > width {
+        let mut truncated: String = cell.chars().take(width.saturating_sub(1)).collect();
+        truncated.push('…');
+        truncated
+    } else {
+        String::from(cell)
+    }
+}
+
 fn format_compact<W: std::io::Write>(
     stdout: &mut W,
     package_histories: &[PackageHistory],
     with_colors: bool,
     without_details: bool,
+    columns: &[Column],
+    width: Option<usize>,
+    palette: &Palette,
 ) -> Result<(), Error> {
-    let (p_max, d_max, a_max, v_max) = max_lens(&package_histories);
+    let columns = compact_columns(package_histories, columns, without_details);
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    // the action of every row to color it, the header has none
+    let mut actions: Vec<Option<&Action>> = Vec::new();
+    if !without_details {
+        rows.push(columns.iter().map(|c| c.header().to_string()).collect());
+        actions.push(None)
+    }
     for package_history in package_histories {
         for event in &package_history.e {
-            match (with_colors, without_details) {
-                (with_colors, true) => {
-                    if with_colors {
-                        match event.a.parse().unwrap() {
-                            Action::Removed => {
-                                write!(stdout, "{red}", red = color::Fg(color::Red))?
-                            }
-                            Action::Downgraded => {
-                                write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
-                            }
-                            _ => write!(stdout, "{green}", green = color::Fg(color::Green))?,
-                        }
-                    }
-                    write!(
-                        stdout,
-                        "|{package: <p_max$}|",
-                        package = package_history.p,
-                        p_max = p_max
-                    )?;
-                    if with_colors {
-                        writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
-                    } else {
-                        writeln!(stdout)?
-                    }
-                }
-                (with_colors, false) => {
-                    if with_colors {
-                        match event.a.parse().unwrap() {
-                            Action::Removed => {
-                                write!(stdout, "{red}", red = color::Fg(color::Red))?
-                            }
-                            Action::Downgraded => {
-                                write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
-                            }
-                            _ => write!(stdout, "{green}", green = color::Fg(color::Green))?,
-                        }
-                    }
-                    write!(
-                        stdout,
-                        "|{package: <p_max$}|{date: <d_max$}|{action: <a_max$}|{version: <v_max$}|",
-                        package = package_history.p,
-                        p_max = p_max,
-                        date = event.d,
-                        d_max = d_max,
-                        action = event.a,
-                        a_max = a_max,
-                        version = event.v,
-                        v_max = v_max
-                    )?;
-                    if with_colors {
-                        writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
-                    } else {
-                        writeln!(stdout)?
-                    }
-                }
-            }
+            rows.push(
+                columns
+                    .iter()
+                    .map(|c| cell(c, &package_history.p, event))
+                    .collect(),
+            );
+            actions.push(Some(&event.action))
+        }
+    }
+    let widths = column_widths(&rows, width);
+    for (row, action) in rows.iter().zip(actions) {
+        if let (true, Some(action)) = (with_colors, action) {
+            write!(stdout, "{}", action_color(palette, action))?
+        }
+        write!(stdout, "|")?;
+        for (cell, width) in row.iter().zip(&widths) {
+            write!(
+                stdout,
+                "{cell: <width$}|",
+                cell = truncate(cell, *width),
+                width = width
+            )?
+        }
+        if with_colors && action.is_some() {
+            writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+        } else {
+            writeln!(stdout)?
         }
     }
     Ok(())
 }
 
-fn max_lens(package_histories: &[PackageHistory]) -> (usize, usize, usize, usize) {
-    let p_max = package_histories.iter().map(|p| p.p.len()).max().unwrap();
-    let events: Vec<Event> = package_histories.iter().flat_map(|p| p.e.clone()).collect();
-    let d_max = events.iter().map(|e| e.d.len()).max().unwrap();
-    let a_max = events.iter().map(|e| e.a.len()).max().unwrap();
-    let v_max = events.iter().map(|e| e.v.len()).max().unwrap();
-    (p_max, d_max, a_max, v_max)
+/*
+ * Only the largest unit, e.g. 45d, 3h, 12m or 30s
+ */
+fn printable_duration(duration: Duration) -> String {
+    if duration.num_days() > 0 {
+        format!("{}d", duration.num_days())
+    } else if duration.num_hours() > 0 {
+        format!("{}h", duration.num_hours())
+    } else if duration.num_minutes() > 0 {
+        format!("{}m", duration.num_minutes())
+    } else {
+        format!("{}s", duration.num_seconds())
+    }
+}
+
+/// Red for removals, yellow for downgrades and green otherwise, unless the palette sets another color.
+pub fn action_color(palette: &Palette, action: &Action) -> color::Fg<color::AnsiValue> {
+    color::Fg(palette.color(action).unwrap_or(match action {
+        Action::Removed => color::AnsiValue(1),
+        Action::Downgraded => color::AnsiValue(3),
+        _ => color::AnsiValue(2),
+    }))
+}
+
+fn on_host(event: &Event) -> String {
+    match &event.host {
+        Some(host) => format!(" on {}", host),
+        None => String::new(),
+    }
+}
+
+/*
+ * Changes made during the current boot are not active yet, the ones made during the previous boot
+ * were activated by the current boot.
+ */
+fn on_boot(event: &Event) -> &'static str {
+    match event.boot {
+        Some(0) => " (pending reboot)",
+        Some(-1) => " (since previous boot)",
+        _ => "",
+    }
 }
 
 fn last_action(package_history: &PackageHistory) -> Action {
     let last_event = package_history.e.last().unwrap();
-    last_event.a.parse().unwrap()
+    last_event.action.clone()
 }
 
 pub trait Printer {
@@ -169,6 +285,7 @@ pub trait Printer {
         &self,
         stdout: &mut W,
         package_histories: &[PackageHistory],
+        palette: &Palette,
     ) -> Result<(), Error>;
 }
 
@@ -177,25 +294,66 @@ impl Printer for Format {
         &self,
         stdout: &mut W,
         package_histories: &[PackageHistory],
+        palette: &Palette,
     ) -> Result<(), Error> {
         match *self {
             Format::Plain {
                 with_colors,
                 without_details,
-            } => format_plain(stdout, package_histories, with_colors, without_details),
+            } => format_plain(
+                stdout,
+                package_histories,
+                with_colors,
+                without_details,
+                palette,
+            ),
             Format::Json { without_details } => {
                 format_json(stdout, package_histories, without_details)
             }
             Format::Compact {
                 with_colors,
                 without_details,
-            } => format_compact(stdout, package_histories, with_colors, without_details),
+                ref columns,
+            } => {
+                // a pseudo terminal may not know its width
+                let width = terminal_size()
+                    .ok()
+                    .map(|(w, _)| w as usize)
+                    .filter(|w| *w > 0);
+                format_compact(
+                    stdout,
+                    package_histories,
+                    with_colors,
+                    without_details,
+                    columns,
+                    width,
+                    palette,
+                )
+            }
+            Format::Timeline { with_colors } => {
+                let width = terminal_size().map_or(80, |(w, _)| w as usize);
+                format_timeline(stdout, package_histories, with_colors, width, palette)
+            }
+            Format::Log { with_colors } => {
+                format_log(stdout, package_histories, with_colors, palette)
+            }
+            Format::Markdown { without_details } => {
+                format_markdown(stdout, package_histories, without_details)
+            }
+            Format::Html { without_details } => {
+                format_html(stdout, package_histories, without_details)
+            }
+            Format::Template { ref template } => {
+                format_template(stdout, package_histories, template)
+            }
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::pkghist::tests::event;
+
     use super::*;
     use Printer;
 
@@ -203,17 +361,14 @@ mod tests {
     fn should_print_json_to_stdout() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Json {
             without_details: false,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(
@@ -226,17 +381,14 @@ mod tests {
     fn should_print_json_to_stdout_no_details() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Json {
             without_details: true,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(str, "[\n  \"foo\"\n]\n")
@@ -247,29 +399,18 @@ mod tests {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
             e: vec![
-                Event {
-                    a: String::from("Upgraded"),
-                    v: String::from("0.0.2"),
-                    d: String::from("2019-08-26 12:00:00"),
-                },
-                Event {
-                    a: String::from("Downgraded"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 13:00:00"),
-                },
-                Event {
-                    a: String::from("Removed"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 14:00:00"),
-                },
+                event("0.0.2", "2019-08-26 12:00:00", Action::Upgraded),
+                event("0.0.1", "2019-08-26 13:00:00", Action::Downgraded),
+                event("0.0.1", "2019-08-26 14:00:00", Action::Removed),
             ],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Plain {
             with_colors: true,
             without_details: false,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(
@@ -282,18 +423,15 @@ mod tests {
     fn should_print_to_stdout_colored_no_details() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Plain {
             with_colors: true,
             without_details: true,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(str, "\u{1b}[38;5;2mfoo\u{1b}[39m\n")
@@ -303,18 +441,15 @@ mod tests {
     fn should_print_to_stdout_no_colors() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Plain {
             with_colors: false,
             without_details: false,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(str, "foo\n  [2019-08-26 12:00:00] Installed\n    0.0.1\n")
@@ -324,18 +459,15 @@ mod tests {
     fn should_print_to_stdout_no_colors_no_details() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Plain {
             with_colors: false,
             without_details: true,
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(str, "foo\n")
@@ -346,36 +478,27 @@ mod tests {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
             e: vec![
-                Event {
-                    a: String::from("Upgraded"),
-                    v: String::from("0.0.2"),
-                    d: String::from("2019-08-26 12:00:00"),
-                },
-                Event {
-                    a: String::from("Downgraded"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 13:00:00"),
-                },
-                Event {
-                    a: String::from("Removed"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 14:00:00"),
-                },
+                event("0.0.2", "2019-08-26 12:00:00", Action::Upgraded),
+                event("0.0.1", "2019-08-26 13:00:00", Action::Downgraded),
+                event("0.0.1", "2019-08-26 14:00:00", Action::Removed),
             ],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Compact {
             with_colors: true,
             without_details: false,
+            columns: Vec::new(),
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(
             str,
-            "\u{1b}[38;5;2m|foo|2019-08-26 12:00:00|Upgraded  |0.0.2|\u{1b}[39m\n\
-             \u{1b}[38;5;3m|foo|2019-08-26 13:00:00|Downgraded|0.0.1|\u{1b}[39m\n\
-             \u{1b}[38;5;1m|foo|2019-08-26 14:00:00|Removed   |0.0.1|\u{1b}[39m\n"
+            "|Package|Date               |Action    |Version|\n\
+             \u{1b}[38;5;2m|foo    |2019-08-26 12:00:00|Upgraded  |0.0.2  |\u{1b}[39m\n\
+             \u{1b}[38;5;3m|foo    |2019-08-26 13:00:00|Downgraded|0.0.1  |\u{1b}[39m\n\
+             \u{1b}[38;5;1m|foo    |2019-08-26 14:00:00|Removed   |0.0.1  |\u{1b}[39m\n"
         )
     }
 
@@ -383,21 +506,125 @@ mod tests {
     fn should_print_compact_to_stdout_no_colors() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Upgraded"),
-                v: String::from("0.0.2"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.2", "2019-08-26 12:00:00", Action::Upgraded)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Compact {
             with_colors: false,
             without_details: false,
+            columns: Vec::new(),
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
-        assert_eq!(str, "|foo|2019-08-26 12:00:00|Upgraded|0.0.2|\n")
+        assert_eq!(
+            str,
+            "|Package|Date               |Action  |Version|\n\
+             |foo    |2019-08-26 12:00:00|Upgraded|0.0.2  |\n"
+        )
+    }
+
+    #[test]
+    fn should_print_hosts() {
+        let mut ev1 = event("0.0.1", "2019-08-26 12:00:00", Action::Installed);
+        ev1.host = Some(String::from("web1"));
+        let mut ev2 = event("0.0.2", "2019-08-26 13:00:00", Action::Installed);
+        ev2.host = Some(String::from("db12"));
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![ev1, ev2],
+            lifetime: None,
+        }];
+        let mut stdout = Vec::new();
+        Format::Plain {
+            with_colors: false,
+            without_details: false,
+        }
+        .print(&mut stdout, &package_histories, &Palette::default())
+        .unwrap();
+        Format::Compact {
+            with_colors: false,
+            without_details: false,
+            columns: Vec::new(),
+        }
+        .print(&mut stdout, &package_histories, &Palette::default())
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "foo\n  [2019-08-26 12:00:00] Installed on web1\n    0.0.1\n  \
+             [2019-08-26 13:00:00] Installed on db12\n    0.0.2\n\
+             |Package|Host|Date               |Action   |Version|\n\
+             |foo    |web1|2019-08-26 12:00:00|Installed|0.0.1  |\n\
+             |foo    |db12|2019-08-26 13:00:00|Installed|0.0.2  |\n"
+        )
+    }
+
+    #[test]
+    fn should_print_boots() {
+        let mut ev1 = event("0.0.1", "2019-08-26 12:00:00", Action::Installed);
+        ev1.boot = Some(-2);
+        let mut ev2 = event("0.0.2", "2019-08-27 12:00:00", Action::Upgraded);
+        ev2.boot = Some(-1);
+        let mut ev3 = event("0.0.3", "2019-08-28 12:00:00", Action::Upgraded);
+        ev3.boot = Some(0);
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![ev1, ev2, ev3],
+            lifetime: None,
+        }];
+        let mut stdout = Vec::new();
+        Format::Plain {
+            with_colors: false,
+            without_details: false,
+        }
+        .print(&mut stdout, &package_histories, &Palette::default())
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "foo\n  [2019-08-26 12:00:00] Installed\n    0.0.1\n  \
+             [2019-08-27 12:00:00] Upgraded (since previous boot)\n    0.0.2\n  \
+             [2019-08-28 12:00:00] Upgraded (pending reboot)\n    0.0.3\n"
+        )
+    }
+
+    #[test]
+    fn should_print_durations() {
+        let mut ev1 = event("0.0.1", "2019-08-26 12:00:00", Action::Installed);
+        ev1.duration = Some(Duration::days(45) + Duration::hours(3));
+        let mut ev2 = event("0.0.2", "2019-10-10 15:00:00", Action::Upgraded);
+        ev2.duration = Some(Duration::minutes(12));
+        let ev3 = event("0.0.2", "2019-10-10 15:12:00", Action::Removed);
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![ev1, ev2, ev3],
+            lifetime: Some(Duration::days(45) + Duration::hours(3) + Duration::minutes(12)),
+        }];
+        let mut stdout = Vec::new();
+        Format::Compact {
+            with_colors: false,
+            without_details: false,
+            columns: Vec::new(),
+        }
+        .print(&mut stdout, &package_histories, &Palette::default())
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "|Package|Date               |Action   |Version|Duration|\n\
+             |foo    |2019-08-26 12:00:00|Installed|0.0.1  |45d     |\n\
+             |foo    |2019-10-10 15:00:00|Upgraded |0.0.2  |12m     |\n\
+             |foo    |2019-10-10 15:12:00|Removed  |0.0.2  |        |\n"
+        )
+    }
+
+    #[test]
+    fn should_print_printable_durations() {
+        assert_eq!(printable_duration(Duration::days(1)), "1d");
+        assert_eq!(printable_duration(Duration::minutes(179)), "2h");
+        assert_eq!(printable_duration(Duration::seconds(30)), "30s")
     }
 
     #[test]
@@ -405,29 +632,19 @@ mod tests {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
             e: vec![
-                Event {
-                    a: String::from("Installed"),
-                    v: String::from("0.0.2"),
-                    d: String::from("2019-08-26 12:00:00"),
-                },
-                Event {
-                    a: String::from("Downgraded"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 13:00:00"),
-                },
-                Event {
-                    a: String::from("Removed"),
-                    v: String::from("0.0.1"),
-                    d: String::from("2019-08-26 14:00:00"),
-                },
+                event("0.0.2", "2019-08-26 12:00:00", Action::Installed),
+                event("0.0.1", "2019-08-26 13:00:00", Action::Downgraded),
+                event("0.0.1", "2019-08-26 14:00:00", Action::Removed),
             ],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Compact {
             with_colors: true,
             without_details: true,
+            columns: Vec::new(),
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(
@@ -442,62 +659,114 @@ mod tests {
     fn should_print_compact_to_stdout_no_details_no_colors() {
         let package_histories = vec![PackageHistory {
             p: String::from("foo"),
-            e: vec![Event {
-                a: String::from("Installed"),
-                v: String::from("0.0.1"),
-                d: String::from("2019-08-26 12:00:00"),
-            }],
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Installed)],
+            lifetime: None,
         }];
         let mut stdout = Vec::new();
         Format::Compact {
             with_colors: false,
             without_details: true,
+            columns: Vec::new(),
         }
-        .print(&mut stdout, &package_histories)
+        .print(&mut stdout, &package_histories, &Palette::default())
         .unwrap();
         let str = String::from_utf8(stdout).unwrap();
         assert_eq!(str, "|foo|\n")
     }
 
     #[test]
-    fn should_get_max_lens() {
-        let package_histories = vec![
-            PackageHistory {
-                p: String::from("foo"),
-                e: vec![
-                    Event {
-                        a: String::from("Installed"),
-                        v: String::from("0.0.1"),
-                        d: String::from("2019-08-26 12:00:00"),
-                    },
-                    Event {
-                        a: String::from("Upgraded"),
-                        v: String::from("0.0.2"),
-                        d: String::from("2019-08-30 13:30:00"),
-                    },
-                ],
-            },
-            PackageHistory {
-                p: String::from("another"),
-                e: vec![
-                    Event {
-                        a: String::from("Installed"),
-                        v: String::from("1.0.1"),
-                        d: String::from("2019-08-27 12:00:00"),
-                    },
-                    Event {
-                        a: String::from("Upgraded"),
-                        v: String::from("1.0.2-deadbeef"),
-                        d: String::from("2019-09-01 13:30:00"),
-                    },
-                ],
-            },
+    fn should_print_with_palette() {
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![
+                event("0.0.1", "2019-08-26 12:00:00", Action::Installed),
+                event("0.0.1", "2019-08-26 13:00:00", Action::Removed),
+            ],
+            lifetime: None,
+        }];
+        let mut colors = std::collections::BTreeMap::new();
+        colors.insert(String::from("installed"), String::from("blue"));
+        colors.insert(String::from("removed"), String::from("light-red"));
+        let palette = Palette::from_config(&colors).unwrap();
+        let mut stdout = Vec::new();
+        Format::Plain {
+            with_colors: true,
+            without_details: false,
+        }
+        .print(&mut stdout, &package_histories, &palette)
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "\u{1b}[38;5;9mfoo\u{1b}[39m\n\
+             \u{1b}[38;5;4m  [2019-08-26 12:00:00] Installed\n    0.0.1\u{1b}[39m\n\
+             \u{1b}[38;5;9m  [2019-08-26 13:00:00] Removed\n    0.0.1\u{1b}[39m\n"
+        )
+    }
+
+    #[test]
+    fn should_get_column_widths() {
+        let rows = vec![
+            vec![String::from("foo"), String::from("0.0.1")],
+            vec![String::from("another"), String::from("1.0.2-deadbeef")],
         ];
-        let (p_max, d_max, a_max, v_max) = max_lens(&package_histories);
-        assert_eq!(p_max, 7);
-        assert_eq!(d_max, 19);
-        assert_eq!(a_max, 9);
-        assert_eq!(v_max, 14)
+        assert_eq!(column_widths(&rows, None), vec![7, 14]);
+        // 3 borders and 16 characters left for the cells
+        assert_eq!(column_widths(&rows, Some(19)), vec![7, 9]);
+        assert_eq!(column_widths(&rows, Some(5)), vec![4, 4])
+    }
+
+    #[test]
+    fn should_not_panic_on_empty_input() {
+        let mut stdout = Vec::new();
+        Format::Compact {
+            with_colors: false,
+            without_details: false,
+            columns: Vec::new(),
+        }
+        .print(&mut stdout, &[], &Palette::default())
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(str, "|Package|Date|Action|Version|\n")
+    }
+
+    #[test]
+    fn should_print_selected_columns_truncated() {
+        let mut upgrade = event("0.0.2-deadbeef", "2019-08-26 14:00:00", Action::Upgraded);
+        upgrade.from = Some("0.0.1".parse().unwrap());
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![upgrade],
+            lifetime: None,
+        }];
+        let mut stdout = Vec::new();
+        format_compact(
+            &mut stdout,
+            &package_histories,
+            false,
+            false,
+            &[Column::Package, Column::From, Column::To],
+            Some(26),
+            &Palette::default(),
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "|Package|From |To        |\n\
+             |foo    |0.0.1|0.0.2-dea…|\n"
+        )
+    }
+
+    #[test]
+    fn should_only_fill_to_for_changed_versions() {
+        let mut upgrade = event("0.0.2", "2019-08-26 14:00:00", Action::Upgraded);
+        upgrade.from = Some("0.0.1".parse().unwrap());
+        let install = event("0.0.1", "2019-08-25 14:00:00", Action::Installed);
+        assert_eq!(cell(&Column::To, "foo", &upgrade), "0.0.2");
+        assert_eq!(cell(&Column::Version, "foo", &upgrade), "0.0.2");
+        assert_eq!(cell(&Column::To, "foo", &install), "");
+        assert_eq!(cell(&Column::Version, "foo", &install), "0.0.1")
     }
 
     #[test]
@@ -505,17 +774,10 @@ mod tests {
         let package_history = PackageHistory {
             p: String::from("another"),
             e: vec![
-                Event {
-                    a: String::from("Installed"),
-                    v: String::from("1.0.1"),
-                    d: String::from("2019-08-27 12:00:00"),
-                },
-                Event {
-                    a: String::from("Removed"),
-                    v: String::from("1.0.2-deadbeef"),
-                    d: String::from("2019-09-01 13:30:00"),
-                },
+                event("1.0.1", "2019-08-27 12:00:00", Action::Installed),
+                event("1.0.2-deadbeef", "2019-09-01 13:30:00", Action::Removed),
             ],
+            lifetime: None,
         };
         let action = last_action(&package_history);
         assert_eq!(action, Action::Removed)
@@ -526,17 +788,10 @@ mod tests {
         let package_history = PackageHistory {
             p: String::from("another"),
             e: vec![
-                Event {
-                    a: String::from("Installed"),
-                    v: String::from("1.0.1"),
-                    d: String::from("2019-08-27 12:00:00"),
-                },
-                Event {
-                    a: String::from("Upgraded"),
-                    v: String::from("1.0.2-deadbeef"),
-                    d: String::from("2019-09-01 13:30:00"),
-                },
+                event("1.0.1", "2019-08-27 12:00:00", Action::Installed),
+                event("1.0.2-deadbeef", "2019-09-01 13:30:00", Action::Upgraded),
             ],
+            lifetime: None,
         };
         let action = last_action(&package_history);
         assert_eq!(action, Action::Upgraded)
@@ -0,0 +1,440 @@
+use std::collections::HashMap;
+use std::io::stdout;
+
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{Config, Format};
+use crate::pacman::action::Action;
+use crate::pacman::transaction;
+use crate::pacman::transaction::Transaction;
+use crate::pacman::PacmanEvent;
+use crate::source::Source;
+
+/// Packages starting with `linux` that are no kernels.
+const NO_KERNELS: [&str; 4] = ["linux-api", "linux-firmware", "linux-tools", "linux-docs"];
+
+/// The flavors of the kernels NVIDIA drivers are built for, e.g. `nvidia-lts` for `linux-lts`.
+const KERNEL_FLAVORS: [&str; 5] = ["-lts", "-zen", "-hardened", "-rt", "-rt-lts"];
+
+/// Which package of a kernel and its module was not changed along with the other one.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Unchanged {
+    Module,
+    Kernel,
+}
+
+/// A module or driver package that was not changed in the transaction changing its kernel, or vice versa.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+struct Mismatch {
+    kernel: String,
+    package: String,
+    /// The installed version of the unchanged package
+    version: String,
+    unchanged: Unchanged,
+}
+
+/// A transaction changing boot-critical packages.
+#[derive(Debug, Serialize, Clone, PartialEq)]
+struct Change {
+    date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<String>,
+    events: Vec<String>,
+    mismatches: Vec<Mismatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+}
+
+pub fn run(config: &Config, packages: &[String]) -> Result<(), Error> {
+    if config.source != Source::Pacman {
+        return Err(Error::new(ErrorDetail::InvalidConfig {
+            msg: String::from("The kernel report is only available for pacman logs"),
+        }));
+    }
+    let mut changes = Vec::new();
+    for (host, logfile) in config.logfiles() {
+        let transactions = transaction::from_logfile(logfile, config.on_skipped_line)?;
+        changes.extend(kernel_changes(&transactions, packages, &config.after, host))
+    }
+    if changes.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_changes(&mut stdout(), &changes, &config.format)
+}
+
+fn is_tracked(package: &str, packages: &[String]) -> bool {
+    package.starts_with("linux")
+        || package.ends_with("-dkms")
+        || package.starts_with("nvidia")
+        || package == "systemd"
+        || package == "mkinitcpio"
+        || packages.iter().any(|p| p == package)
+}
+
+pub(crate) fn is_kernel(package: &str) -> bool {
+    package.starts_with("linux")
+        && !package.ends_with("-headers")
+        && !NO_KERNELS.iter().any(|k| package.starts_with(k))
+}
+
+/*
+ * The kernel a module or driver package is built for, e.g. linux-lts for linux-lts-headers
+ * and nvidia-lts. DKMS modules are rebuilt using the headers, so only the headers are checked.
+ * Userspace packages like nvidia-utils or nvidia-settings aren't built for a kernel.
+ */
+fn kernel_of(package: &str) -> Option<String> {
+    if let Some(kernel) = package.strip_suffix("-headers") {
+        return Some(String::from(kernel)).filter(|k| is_kernel(k));
+    }
+    if package.ends_with("-dkms") {
+        return None;
+    }
+    let flavor = package.strip_prefix("nvidia")?;
+    let flavor = flavor.strip_prefix("-open").unwrap_or(flavor);
+    if flavor.is_empty() || KERNEL_FLAVORS.contains(&flavor) {
+        Some(format!("linux{}", flavor))
+    } else {
+        None
+    }
+}
+
+/*
+ * Replays the transactions to know which modules were installed when a kernel was up- or downgraded.
+ */
+fn kernel_changes(
+    transactions: &[Transaction],
+    packages: &[String],
+    after: &Option<NaiveDateTime>,
+    host: Option<&str>,
+) -> Vec<Change> {
+    let mut installed: HashMap<String, String> = HashMap::new();
+    let mut changes = Vec::new();
+    for transaction in transactions {
+        let mut mismatches = Vec::new();
+        for kernel in transaction
+            .events
+            .iter()
+            .filter(|e| e.action == Action::Upgraded || e.action == Action::Downgraded)
+            .filter(|e| is_kernel(&e.package))
+        {
+            let mut modules: Vec<(&String, &String)> = installed
+                .iter()
+                .filter(|(m, _)| kernel_of(m).as_ref() == Some(&kernel.package))
+                .filter(|(m, _)| !transaction.events.iter().any(|e| &e.package == *m))
+                .collect();
+            modules.sort();
+            mismatches.extend(modules.into_iter().map(|(module, version)| Mismatch {
+                kernel: kernel.package.clone(),
+                package: module.clone(),
+                version: version.clone(),
+                unchanged: Unchanged::Module,
+            }))
+        }
+        for module in transaction
+            .events
+            .iter()
+            .filter(|e| e.action == Action::Upgraded || e.action == Action::Downgraded)
+        {
+            let kernel = match kernel_of(&module.package) {
+                Some(kernel) => kernel,
+                None => continue,
+            };
+            if transaction.events.iter().any(|e| e.package == kernel) {
+                continue;
+            }
+            if let Some(version) = installed.get(&kernel) {
+                mismatches.push(Mismatch {
+                    kernel,
+                    package: module.package.clone(),
+                    version: version.clone(),
+                    unchanged: Unchanged::Kernel,
+                })
+            }
+        }
+        for event in &transaction.events {
+            match event.action {
+                Action::Removed => installed.remove(&event.package),
+                _ => installed.insert(event.package.clone(), event.printable_version()),
+            };
+        }
+
+        let events: Vec<&PacmanEvent> = transaction
+            .events
+            .iter()
+            .filter(|e| is_tracked(&e.package, packages))
+            .collect();
+        let is_after = match after {
+            Some(a) => transaction.date > *a,
+            None => true,
+        };
+        if !events.is_empty() && is_after {
+            changes.push(Change {
+                date: transaction.date.to_string(),
+                command: transaction.command.clone(),
+                events: events.iter().map(|e| printable_event(e)).collect(),
+                mismatches,
+                host: host.map(String::from),
+            })
+        }
+    }
+    changes
+}
+
+fn printable_event(pacman_event: &PacmanEvent) -> String {
+    match &pacman_event.to {
+        Some(to) => format!(
+            "{} {} ({} -> {})",
+            pacman_event.action, pacman_event.package, pacman_event.from, to
+        ),
+        None => format!(
+            "{} {} ({})",
+            pacman_event.action, pacman_event.package, pacman_event.from
+        ),
+    }
+}
+
+fn print_changes<W: std::io::Write>(
+    stdout: &mut W,
+    changes: &[Change],
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        writeln!(stdout, "{}", serde_json::to_string_pretty(changes).unwrap())?;
+        return Ok(());
+    }
+    let with_colors = format.with_colors();
+    for change in changes {
+        write!(stdout, "[{}]", change.date)?;
+        if let Some(command) = &change.command {
+            write!(stdout, " {}", command)?
+        }
+        if let Some(host) = &change.host {
+            write!(stdout, " on {}", host)?
+        }
+        writeln!(stdout)?;
+        for event in &change.events {
+            writeln!(stdout, "  {}", event)?
+        }
+        for mismatch in &change.mismatches {
+            if with_colors {
+                write!(stdout, "{red}", red = color::Fg(color::Red))?
+            }
+            match mismatch.unchanged {
+                Unchanged::Module => write!(
+                    stdout,
+                    "  {package} {version} was not changed with {kernel}",
+                    package = mismatch.package,
+                    version = mismatch.version,
+                    kernel = mismatch.kernel
+                )?,
+                Unchanged::Kernel => write!(
+                    stdout,
+                    "  {kernel} {version} was not changed with {package}",
+                    kernel = mismatch.kernel,
+                    version = mismatch.version,
+                    package = mismatch.package
+                )?,
+            }
+            if with_colors {
+                writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+            } else {
+                writeln!(stdout)?
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+
+    fn transaction(minute: u32, command: &str, events: &[&str]) -> Transaction {
+        let mut transaction = Transaction::new(
+            NaiveDateTime::new(
+                NaiveDate::from_ymd_opt(2024, 1, 14).unwrap(),
+                NaiveTime::from_hms_opt(21, minute, 0).unwrap(),
+            ),
+            Some(String::from(command)),
+        );
+        transaction.events = events
+            .iter()
+            .map(|e| e.parse::<PacmanEvent>().unwrap())
+            .collect();
+        transaction
+    }
+
+    #[test]
+    fn should_find_kernel_of_modules() {
+        assert_eq!(
+            kernel_of("linux-lts-headers"),
+            Some(String::from("linux-lts"))
+        );
+        assert_eq!(kernel_of("nvidia"), Some(String::from("linux")));
+        assert_eq!(
+            kernel_of("nvidia-open-lts"),
+            Some(String::from("linux-lts"))
+        );
+        assert_eq!(kernel_of("nvidia-dkms"), None);
+        assert_eq!(kernel_of("nvidia-utils"), None);
+        assert_eq!(kernel_of("nvidia-settings"), None);
+        assert_eq!(kernel_of("linux-api-headers"), None);
+        assert_eq!(kernel_of("systemd"), None)
+    }
+
+    #[test]
+    fn should_track_boot_critical_packages() {
+        let packages = [String::from("grub")];
+        assert!(is_tracked("linux-zen", &packages));
+        assert!(is_tracked("v4l2loopback-dkms", &packages));
+        assert!(is_tracked("nvidia-utils", &packages));
+        assert!(is_tracked("mkinitcpio", &packages));
+        assert!(is_tracked("grub", &packages));
+        assert!(!is_tracked("vim", &packages))
+    }
+
+    #[test]
+    fn should_flag_modules_not_changed_with_kernel() {
+        let transactions = vec![
+            transaction(
+                30,
+                "pacman -S linux linux-headers nvidia vim",
+                &[
+                    "[2024-01-14 21:30] [ALPM] installed linux (6.6.9-1)",
+                    "[2024-01-14 21:30] [ALPM] installed linux-headers (6.6.9-1)",
+                    "[2024-01-14 21:30] [ALPM] installed nvidia (545.29.06-3)",
+                    "[2024-01-14 21:30] [ALPM] installed vim (9.0-1)",
+                ],
+            ),
+            transaction(
+                31,
+                "pacman -Syu",
+                &[
+                    "[2024-01-14 21:31] [ALPM] upgraded linux (6.6.9-1 -> 6.7.1-1)",
+                    "[2024-01-14 21:31] [ALPM] upgraded linux-headers (6.6.9-1 -> 6.7.1-1)",
+                ],
+            ),
+            transaction(
+                32,
+                "pacman -Syu",
+                &["[2024-01-14 21:32] [ALPM] upgraded vim (9.0-1 -> 9.1-1)"],
+            ),
+        ];
+
+        let changes = kernel_changes(&transactions, &[], &None, None);
+
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].events.len(), 3);
+        assert!(changes[0].mismatches.is_empty());
+        assert_eq!(
+            changes[1],
+            Change {
+                date: String::from("2024-01-14 21:31:00"),
+                command: Some(String::from("pacman -Syu")),
+                events: vec![
+                    String::from("Upgraded linux (6.6.9-1 -> 6.7.1-1)"),
+                    String::from("Upgraded linux-headers (6.6.9-1 -> 6.7.1-1)"),
+                ],
+                mismatches: vec![Mismatch {
+                    kernel: String::from("linux"),
+                    package: String::from("nvidia"),
+                    version: String::from("545.29.06-3"),
+                    unchanged: Unchanged::Module,
+                }],
+                host: None,
+            }
+        )
+    }
+
+    #[test]
+    fn should_flag_modules_changed_without_kernel() {
+        let transactions = vec![
+            transaction(
+                30,
+                "pacman -S linux nvidia nvidia-utils",
+                &[
+                    "[2024-01-14 21:30] [ALPM] installed linux (6.6.9-1)",
+                    "[2024-01-14 21:30] [ALPM] installed nvidia (545.29.06-3)",
+                    "[2024-01-14 21:30] [ALPM] installed nvidia-utils (545.29.06-1)",
+                ],
+            ),
+            transaction(
+                31,
+                "pacman -Syu",
+                &[
+                    "[2024-01-14 21:31] [ALPM] upgraded nvidia (545.29.06-3 -> 545.29.06-4)",
+                    "[2024-01-14 21:31] [ALPM] upgraded nvidia-utils (545.29.06-1 -> 545.29.06-2)",
+                ],
+            ),
+            transaction(
+                32,
+                "pacman -Syu",
+                &[
+                    "[2024-01-14 21:32] [ALPM] upgraded linux (6.6.9-1 -> 6.7.1-1)",
+                    "[2024-01-14 21:32] [ALPM] upgraded nvidia (545.29.06-4 -> 545.29.06-5)",
+                ],
+            ),
+        ];
+
+        let changes = kernel_changes(&transactions, &[], &None, None);
+
+        assert_eq!(
+            changes[1].mismatches,
+            vec![Mismatch {
+                kernel: String::from("linux"),
+                package: String::from("nvidia"),
+                version: String::from("6.6.9-1"),
+                unchanged: Unchanged::Kernel,
+            }]
+        );
+        assert!(changes[2].mismatches.is_empty())
+    }
+
+    #[test]
+    fn should_print_changes_no_colors() {
+        let changes = vec![Change {
+            date: String::from("2024-01-14 21:31:00"),
+            command: Some(String::from("pacman -Syu")),
+            events: vec![String::from("Upgraded linux (6.6.9-1 -> 6.7.1-1)")],
+            mismatches: vec![
+                Mismatch {
+                    kernel: String::from("linux"),
+                    package: String::from("nvidia"),
+                    version: String::from("545.29.06-3"),
+                    unchanged: Unchanged::Module,
+                },
+                Mismatch {
+                    kernel: String::from("linux-lts"),
+                    package: String::from("nvidia-lts"),
+                    version: String::from("6.1.71-1"),
+                    unchanged: Unchanged::Kernel,
+                },
+            ],
+            host: Some(String::from("web1")),
+        }];
+        let mut stdout = Vec::new();
+        print_changes(
+            &mut stdout,
+            &changes,
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "[2024-01-14 21:31:00] pacman -Syu on web1\n  \
+             Upgraded linux (6.6.9-1 -> 6.7.1-1)\n  \
+             nvidia 545.29.06-3 was not changed with linux\n  \
+             linux-lts 6.1.71-1 was not changed with nvidia-lts\n"
+        )
+    }
+}
@@ -0,0 +1,150 @@
+use std::env;
+use std::io::{stdout, Write};
+use std::process::{Command, Stdio};
+
+use termion::color;
+
+use crate::error::{Error, ErrorDetail};
+use crate::opt::Palette;
+use crate::pkghist::format::action_color;
+use crate::pkghist::{Event, PackageHistory};
+
+/*
+ * One line per event, newest first, like `git log --oneline`
+ */
+pub fn format_log<W: Write>(
+    stdout: &mut W,
+    package_histories: &[PackageHistory],
+    with_colors: bool,
+    palette: &Palette,
+) -> Result<(), Error> {
+    let mut events: Vec<(&String, &Event)> = package_histories
+        .iter()
+        .flat_map(|p| p.e.iter().map(move |e| (&p.p, e)))
+        .collect();
+    events.sort_by(|(p1, e1), (p2, e2)| e2.date.cmp(&e1.date).then_with(|| p1.cmp(p2)));
+    let a_max = events
+        .iter()
+        .map(|(_, e)| e.action.to_string().len())
+        .max()
+        .unwrap_or(0);
+    for (package, event) in events {
+        let version = match &event.from {
+            Some(from) => format!("{} -> {}", from, event.version),
+            None => event.version.to_string(),
+        };
+        if with_colors {
+            write!(
+                stdout,
+                "{yellow}{date}{reset} {action_color}{action: <a_max$}{reset} {package} {version}",
+                yellow = color::Fg(color::Yellow),
+                date = event.printable_date(),
+                action_color = action_color(palette, &event.action),
+                action = event.action.to_string(),
+                a_max = a_max,
+                package = package,
+                version = version,
+                reset = color::Fg(color::Reset)
+            )?
+        } else {
+            write!(
+                stdout,
+                "{date} {action: <a_max$} {package} {version}",
+                date = event.printable_date(),
+                action = event.action.to_string(),
+                a_max = a_max,
+                package = package,
+                version = version
+            )?
+        }
+        match &event.host {
+            Some(host) => writeln!(stdout, " on {}", host)?,
+            None => writeln!(stdout)?,
+        }
+    }
+    Ok(())
+}
+
+/// Pipes the output through `$PAGER` (default `less -R`), an empty `$PAGER` or a pager that can't be
+/// started prints to stdout.
+pub fn page<F>(print: F) -> Result<(), Error>
+where
+    F: FnOnce(&mut dyn Write) -> Result<(), Error>,
+{
+    let pager = env::var("PAGER").unwrap_or_else(|_| String::from("less -R"));
+    let mut args = pager.split_whitespace();
+    let child = args.next().and_then(|program| {
+        Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .ok()
+    });
+    let mut child = match child {
+        Some(child) => child,
+        None => return print(&mut stdout()),
+    };
+    let result = print(child.stdin.as_mut().unwrap());
+    // closing stdin lets the pager know the output is complete
+    drop(child.stdin.take());
+    child.wait()?;
+    match result {
+        // the pager was quit before reading everything
+        Err(e) if *e.detail() == ErrorDetail::BrokenPipe => Ok(()),
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pacman::action::Action;
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    #[test]
+    fn should_print_log_newest_first() {
+        let mut upgrade = event("0.0.2", "2019-08-26 14:00:00", Action::Upgraded);
+        upgrade.from = Some("0.0.1".parse().unwrap());
+        let package_histories = vec![
+            PackageHistory {
+                p: String::from("foo"),
+                e: vec![
+                    event("0.0.1", "2019-08-26 12:00:00", Action::Installed),
+                    upgrade,
+                ],
+                lifetime: None,
+            },
+            PackageHistory {
+                p: String::from("bar"),
+                e: vec![event("1.0", "2019-08-26 13:00:00", Action::Removed)],
+                lifetime: None,
+            },
+        ];
+        let mut stdout = Vec::new();
+        format_log(&mut stdout, &package_histories, false, &Palette::default()).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "2019-08-26 14:00:00 Upgraded  foo 0.0.1 -> 0.0.2\n\
+             2019-08-26 13:00:00 Removed   bar 1.0\n\
+             2019-08-26 12:00:00 Installed foo 0.0.1\n"
+        )
+    }
+
+    #[test]
+    fn should_print_log_colored() {
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![event("0.0.1", "2019-08-26 12:00:00", Action::Removed)],
+            lifetime: None,
+        }];
+        let mut stdout = Vec::new();
+        format_log(&mut stdout, &package_histories, true, &Palette::default()).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "\u{1b}[38;5;3m2019-08-26 12:00:00\u{1b}[39m \u{1b}[38;5;1mRemoved\u{1b}[39m foo 0.0.1\n"
+        )
+    }
+}
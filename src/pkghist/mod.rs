@@ -1,29 +1,223 @@
+mod activity;
+mod advisories;
+mod audit;
+mod fleet;
 mod format;
+mod kernel;
+mod log;
+pub mod query;
+mod reboot;
+mod report;
+pub mod template;
+mod timeline;
+mod tui;
 
-use std::path::Path;
+use std::collections::HashMap;
+use std::env;
+use std::hash::BuildHasher;
 
 use serde::Deserialize;
 use serde::Serialize;
 
-use crate::error::Error;
-use crate::opt::Config;
-use crate::pacman;
+use crate::error::{Error, ErrorDetail};
+use crate::opt::{ColorMode, Config, Format, Mode};
+use crate::pacman::action::Action;
 use crate::pacman::filter::Filter;
+use crate::pacman::group::Group;
+use crate::pacman::version::Version;
 use crate::pacman::PacmanEvent;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
 use itertools::Itertools;
 use std::io::stdout;
 
 use crate::pkghist::format::Printer;
 
-pub fn run(config: Config) -> Result<(), Error> {
-    let logfile_path = &config.logfile;
-    let pacman_events = pacman::from_file(Path::new(logfile_path)).unwrap_or_else(|_| {
-        eprintln!("Unable to open {}", logfile_path);
-        std::process::exit(2)
-    });
+pub fn run(mut config: Config) -> Result<(), Error> {
+    if config.color == ColorMode::Auto && !colors_supported() {
+        config.format.disable_colors()
+    }
+    match config.mode {
+        Mode::History => history(config),
+        Mode::Audit { churn_window } => audit::run(&config, churn_window),
+        Mode::Tui => tui::run(config),
+        Mode::Activity => activity::run(&config),
+        Mode::Fleet => fleet::run(&config),
+        Mode::Advisories { ref file } => advisories::run(&config, file),
+        Mode::Kernel { ref packages } => kernel::run(&config, packages),
+        Mode::Reboot => reboot::run(&config),
+    }
+}
+
+/*
+ * Colors are only written to a terminal and never if NO_COLOR is set (see https://no-color.org)
+ */
+fn colors_supported() -> bool {
+    termion::is_tty(&stdout()) && env::var_os("NO_COLOR").map_or(true, |v| v.is_empty())
+}
+
+fn history(config: Config) -> Result<(), Error> {
+    let package_histories = read(&config)?;
+    if package_histories.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+
+    if let Format::Log { .. } = config.format {
+        if termion::is_tty(&stdout()) {
+            return log::page(|mut out| {
+                config
+                    .format
+                    .print(&mut out, &package_histories, &config.palette)
+            });
+        }
+    }
+    config
+        .format
+        .print(&mut stdout(), &package_histories, &config.palette)
+}
+
+/// Reads the logfile of the given config and returns the filtered package histories.
+///
+/// Nothing is printed, an unreadable logfile is returned as `ErrorDetail::UnreadableLogfile`.
+/// The histories of all hosts are merged, their events are tagged with the host's name.
+pub fn read(config: &Config) -> Result<Vec<PackageHistory>, Error> {
+    let boots = reboot::boots_of(config)?;
+    let host_events = read_hosts(config)?;
+    let mut package_histories = host_histories(&host_events, config);
+    if let Some(boots) = boots {
+        for event in package_histories.iter_mut().flat_map(|p| p.e.iter_mut()) {
+            event.boot = reboot::boot_index(&boots, &event.date)
+        }
+    }
+    Ok(package_histories)
+}
+
+/// The events read from the logfile of a host, `None` if no hosts are given.
+pub(crate) type HostEvents = (Option<String>, Vec<PacmanEvent>);
+
+pub(crate) fn read_hosts(config: &Config) -> Result<Vec<HostEvents>, Error> {
+    config
+        .logfiles()
+        .into_iter()
+        .map(|(host, logfile)| {
+            let pacman_events = config
+                .source
+                .log_source()
+                .read_logfile(logfile, config.on_skipped_line)?;
+            Ok((host.map(String::from), pacman_events))
+        })
+        .collect()
+}
+
+/// Selects the events of a package on a host, e.g. `Filter::filter_packages`.
+type Select =
+    for<'a> fn(&'a Vec<PacmanEvent>, &Config) -> HashMap<&'a String, Vec<&'a PacmanEvent>>;
 
-    let groups = pacman_events.filter_packages(&config);
+pub(crate) fn host_histories(host_events: &[HostEvents], config: &Config) -> Vec<PackageHistory> {
+    select_histories(host_events, config, Filter::filter_packages)
+}
+
+/// The complete histories of the packages passing the filters, e.g. to tell the current version of a package.
+///
+/// Dates, actions, expressions and limits are ignored, removed packages are kept.
+pub(crate) fn all_histories(host_events: &[HostEvents], config: &Config) -> Vec<PackageHistory> {
+    select_histories(host_events, config, Filter::select_packages)
+}
+
+/*
+ * Every host is filtered on its own, e.g. --with-removed looks at the state of the package on each host
+ */
+fn select_histories(
+    host_events: &[HostEvents],
+    config: &Config,
+    select: Select,
+) -> Vec<PackageHistory> {
+    let mut histories = Vec::new();
+    let now = Utc::now().with_timezone(&FixedOffset::east_opt(0).unwrap());
+    for (host, pacman_events) in host_events {
+        let groups = select(pacman_events, config);
+        let mut tagged = package_histories(&groups);
+        if config.durations {
+            let all = pacman_events.group();
+            for package_history in tagged.iter_mut() {
+                set_durations(package_history, &all[&package_history.p], now)
+            }
+        }
+        for event in tagged.iter_mut().flat_map(|p| p.e.iter_mut()) {
+            event.host = host.clone()
+        }
+        histories.extend(tagged)
+    }
+    if host_events.len() > 1 {
+        merge_hosts(histories)
+    } else {
+        histories
+    }
+}
 
+/*
+ * The durations are taken from all events of the package, the filtered events may skip some of them.
+ * A version is installed until the next event, the current version until now.
+ */
+fn set_durations(
+    package_history: &mut PackageHistory,
+    pacman_events: &[&PacmanEvent],
+    now: DateTime<FixedOffset>,
+) {
+    let mut sorted = pacman_events.to_vec();
+    sorted.sort();
+    let all = PackageHistory::from_pacman_events(sorted).e;
+    let durations: Vec<Option<Duration>> = all
+        .iter()
+        .enumerate()
+        .map(|(idx, event)| {
+            if event.action.is_removed() {
+                return None;
+            }
+            let end = all.get(idx + 1).map_or(now, |next| next.date);
+            Some(end.signed_duration_since(event.date))
+        })
+        .collect();
+    for event in package_history.e.iter_mut() {
+        if let Some(idx) = all.iter().position(|e| e == event) {
+            event.duration = durations[idx]
+        }
+    }
+    package_history.lifetime = Some(
+        durations
+            .iter()
+            .flatten()
+            .fold(Duration::zero(), |lifetime, duration| lifetime + *duration),
+    )
+}
+
+/*
+ * Joins the histories of a package on different hosts, ordered like a single host's histories
+ */
+fn merge_hosts(package_histories: Vec<PackageHistory>) -> Vec<PackageHistory> {
+    let mut merged: HashMap<String, Vec<Event>> = HashMap::new();
+    for package_history in package_histories {
+        merged
+            .entry(package_history.p)
+            .or_default()
+            .extend(package_history.e)
+    }
+    merged
+        .into_iter()
+        .map(|(p, mut e)| {
+            e.sort_by(|e1, e2| e1.date.cmp(&e2.date).then_with(|| e1.host.cmp(&e2.host)));
+            PackageHistory::new(p, e)
+        })
+        .sorted_by(|h1, h2| {
+            let d1 = h1.e.last().unwrap().date;
+            let d2 = h2.e.last().unwrap().date;
+            d1.cmp(&d2).then_with(|| h1.p.cmp(&h2.p))
+        })
+        .collect()
+}
+
+fn package_histories<S: BuildHasher>(
+    groups: &HashMap<&String, Vec<&PacmanEvent>, S>,
+) -> Vec<PackageHistory> {
     let mut package_histories = Vec::new();
 
     let sorted: Vec<Vec<&PacmanEvent>> = groups
@@ -45,56 +239,176 @@ pub fn run(config: Config) -> Result<(), Error> {
         let package_history = PackageHistory::from_pacman_events(events);
         package_histories.push(package_history);
     }
-
-    match config.format.print(&mut stdout(), &package_histories) {
-        _ => Ok(()),
-    }
+    package_histories
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Ord, Eq, PartialOrd, PartialEq)]
+/// A single change of a package.
+///
+/// Serialized using the short names `v`, `d`, `a`, `f` (the previous version of up- and downgrades)
+/// `h` (the host, only if read using `--host`), `du` (the seconds the version stayed installed, only using `--durations`)
+/// and `b` (the index of the boot the change was made in, only using `--boots`).
+#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct Event {
-    pub v: String,
-    pub d: String,
-    pub a: String,
+    #[serde(rename = "v")]
+    pub version: Version,
+    #[serde(rename = "d", with = "date_format")]
+    pub date: DateTime<FixedOffset>,
+    #[serde(rename = "a")]
+    pub action: Action,
+    #[serde(rename = "f", default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<Version>,
+    #[serde(rename = "h", default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(
+        rename = "du",
+        default,
+        with = "duration_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub duration: Option<Duration>,
+    #[serde(rename = "b", default, skip_serializing_if = "Option::is_none")]
+    pub boot: Option<i64>,
 }
 
 impl Event {
-    fn new(version: String, date: String, action: String) -> Event {
+    fn new(version: Version, date: DateTime<FixedOffset>, action: Action) -> Event {
         Event {
-            v: version,
-            d: date,
-            a: action,
+            version,
+            date,
+            action,
+            from: None,
+            host: None,
+            duration: None,
+            boot: None,
         }
     }
 
-    fn from_pacman_event(pacman_event: &PacmanEvent) -> Event {
-        Event::new(
-            pacman_event.printable_version(),
-            pacman_event.date.to_string(),
-            pacman_event.action.to_string(),
-        )
+    fn from_pacman_event(pacman_event: &PacmanEvent) -> Result<Event, Error> {
+        let (version, from) = pacman_event.versions()?;
+        let mut event = Event::new(
+            version,
+            pacman_event.timestamp(),
+            pacman_event.action.clone(),
+        );
+        event.from = from;
+        Ok(event)
+    }
+
+    /// The date as logged by pacman, in the local time of the event.
+    pub fn printable_date(&self) -> String {
+        self.date.format(date_format::FORMAT).to_string()
     }
 }
 
+/*
+ * The date is serialized in the local time of the event (as logged by pacman) to keep the output stable,
+ * dates without an offset are read as local time.
+ */
+mod date_format {
+    use chrono::{DateTime, FixedOffset, NaiveDateTime};
+    use serde::de::Error;
+
+    use crate::pacman::local_timestamp;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub const FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+    pub fn serialize<S: Serializer>(
+        date: &DateTime<FixedOffset>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&date.format(FORMAT))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<FixedOffset>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        if let Ok(date) = DateTime::parse_from_rfc3339(&s) {
+            return Ok(date);
+        }
+        let naive = NaiveDateTime::parse_from_str(&s, FORMAT).map_err(D::Error::custom)?;
+        Ok(local_timestamp(&naive))
+    }
+}
+
+/*
+ * Durations are serialized in seconds
+ */
+mod duration_format {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match duration {
+            Some(duration) => serializer.serialize_i64(duration.num_seconds()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Ok(Option::<i64>::deserialize(deserializer)?.map(Duration::seconds))
+    }
+}
+
+/// The events of a package, `lt` is the total time it was installed (only using `--durations`).
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PackageHistory {
     pub p: String,
     pub e: Vec<Event>,
+    #[serde(
+        rename = "lt",
+        default,
+        with = "duration_format",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub lifetime: Option<Duration>,
 }
 
 impl PackageHistory {
     fn new(p: String, e: Vec<Event>) -> PackageHistory {
-        PackageHistory { p, e }
+        PackageHistory {
+            p,
+            e,
+            lifetime: None,
+        }
     }
 
     fn from_pacman_events(pacman_events: Vec<&PacmanEvent>) -> PackageHistory {
         let e: Vec<Event> = pacman_events
             .iter()
-            .map(|e| Event::from_pacman_event(e))
+            // the versions are checked when the logfile is read
+            .filter_map(|e| Event::from_pacman_event(e).ok())
             .collect();
         let p = pacman_events.first().unwrap().package.clone();
         PackageHistory::new(p, e)
     }
+
+    /// Splits the history into one history per host, in the order the hosts first appear.
+    pub fn split_hosts(&self) -> Vec<(Option<&str>, PackageHistory)> {
+        let mut hosts: Vec<(Option<&str>, PackageHistory)> = Vec::new();
+        for event in &self.e {
+            let host = event.host.as_deref();
+            match hosts.iter_mut().find(|(h, _)| *h == host) {
+                Some((_, package_history)) => package_history.e.push(event.clone()),
+                None => hosts.push((
+                    host,
+                    PackageHistory::new(self.p.clone(), vec![event.clone()]),
+                )),
+            }
+        }
+        hosts
+    }
+
+    /// Whether any event is tagged with a host.
+    pub fn has_hosts(&self) -> bool {
+        self.e.iter().any(|e| e.host.is_some())
+    }
 }
 
 #[cfg(test)]
@@ -105,23 +419,23 @@ mod tests {
 
     use filepath::FilePath;
 
-    use crate::pacman::action::Action;
-
     use super::*;
-    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+
+    /// Creates an event at the given UTC date, e.g. `event("0.0.1", "2019-08-26 12:00:00", Action::Installed)`.
+    pub(crate) fn event(version: &str, date: &str, action: Action) -> Event {
+        let date = NaiveDateTime::parse_from_str(date, date_format::FORMAT).unwrap();
+        Event::new(
+            version.parse().unwrap(),
+            FixedOffset::east_opt(0).unwrap().from_utc_datetime(&date),
+            action,
+        )
+    }
 
     #[test]
     fn should_create_package_histories_with_new() {
-        let ev1 = Event::new(
-            String::from("1.2.1"),
-            String::from("2019-10-01 12:30:00"),
-            String::from("Upgraded"),
-        );
-        let ev2 = Event::new(
-            String::from("1.2.1"),
-            String::from("2019-10-01 13:30:00"),
-            String::from("Removed"),
-        );
+        let ev1 = event("1.2.1", "2019-10-01 12:30:00", Action::Upgraded);
+        let ev2 = event("1.2.1", "2019-10-01 13:30:00", Action::Removed);
 
         let package_histories =
             PackageHistory::new(String::from("foo"), vec![ev1.clone(), ev2.clone()]);
@@ -159,21 +473,172 @@ mod tests {
         let package_history = PackageHistory::from_pacman_events(pacman_events);
         assert_eq!(package_history.p, "test");
         assert_eq!(package_history.e.len(), 2);
+        let dates: Vec<String> = package_history
+            .e
+            .iter()
+            .map(|e| e.printable_date())
+            .collect();
+        assert_eq!(dates, vec!["2019-09-01 12:30:00", "2019-09-01 18:30:10"]);
+        assert_eq!(
+            package_history.e[0].version,
+            Version::new(None, "0.1.0", None)
+        );
+        assert_eq!(package_history.e[0].action, Action::Installed);
+        assert_eq!(package_history.e[0].from, None);
+        assert_eq!(
+            package_history.e[1].version,
+            Version::new(None, "0.1.1", None)
+        );
+        assert_eq!(package_history.e[1].action, Action::Upgraded);
+        assert_eq!(
+            package_history.e[1].from,
+            Some(Version::new(None, "0.1.0", None))
+        )
+    }
+
+    #[test]
+    fn should_serialize_event() {
+        let mut upgraded = event("0.1.1", "2019-09-01 18:30:10", Action::Upgraded);
+        upgraded.from = Some("0.1.0".parse().unwrap());
+        let json = serde_json::to_string(&upgraded).unwrap();
+        assert_eq!(
+            json,
+            "{\"v\":\"0.1.1\",\"d\":\"2019-09-01 18:30:10\",\"a\":\"Upgraded\",\"f\":\"0.1.0\"}"
+        );
+        let deserialized: Event =
+            serde_json::from_str("{\"v\":\"0.1.1\",\"d\":\"2019-09-01T18:30:10+00:00\",\"a\":\"Upgraded\",\"f\":\"0.1.0\"}")
+                .unwrap();
+        assert_eq!(deserialized, upgraded)
+    }
+
+    #[test]
+    fn should_split_hosts() {
+        let mut ev1 = event("1.0-1", "2019-10-01 12:30:00", Action::Installed);
+        ev1.host = Some(String::from("web1"));
+        let mut ev2 = event("1.0-1", "2019-10-01 13:30:00", Action::Installed);
+        ev2.host = Some(String::from("web2"));
+        let mut ev3 = event("1.1-1", "2019-10-02 12:30:00", Action::Upgraded);
+        ev3.host = Some(String::from("web1"));
+        let package_history = PackageHistory::new(String::from("foo"), vec![ev1, ev2, ev3]);
+
+        let hosts = package_history.split_hosts();
+
+        assert!(package_history.has_hosts());
+        assert_eq!(hosts.len(), 2);
+        assert_eq!(hosts[0].0, Some("web1"));
+        assert_eq!(hosts[0].1.e.len(), 2);
+        assert_eq!(hosts[1].0, Some("web2"));
+        assert_eq!(hosts[1].1.e.len(), 1)
+    }
+
+    #[test]
+    fn should_read_hosts() {
+        let web1 = uuid::Uuid::new_v4().to_string();
+        let mut file1 = File::create(&web1).unwrap();
+        writeln!(
+            file1,
+            "[2019-07-14 21:33] [ALPM] installed feh (3.1.3-1)\n\
+             [2019-07-16 21:33] [ALPM] upgraded feh (3.1.3-1 -> 3.2-1)"
+        )
+        .unwrap();
+        let web2 = uuid::Uuid::new_v4().to_string();
+        let mut file2 = File::create(&web2).unwrap();
+        writeln!(
+            file2,
+            "[2019-07-15 21:33] [ALPM] installed feh (3.1.3-1)\n\
+             [2019-07-15 21:34] [ALPM] installed libev (4.27-1)\n\
+             [2019-07-17 21:34] [ALPM] removed libev (4.27-1)"
+        )
+        .unwrap();
+
+        let mut config = Config::new();
+        config.hosts = vec![
+            format!("web1={}", web1).parse().unwrap(),
+            format!("web2={}", web2).parse().unwrap(),
+        ];
+        let package_histories = read(&config).unwrap();
+
+        assert_eq!(package_histories.len(), 1);
+        assert_eq!(package_histories[0].p, "feh");
+        let hosts: Vec<Option<String>> = package_histories[0]
+            .e
+            .iter()
+            .map(|e| e.host.clone())
+            .collect();
         assert_eq!(
-            package_history.e,
+            hosts,
             vec![
-                Event::new(
-                    String::from("0.1.0"),
-                    String::from("2019-09-01 12:30:00"),
-                    String::from("Installed")
-                ),
-                Event::new(
-                    String::from("0.1.1"),
-                    String::from("2019-09-01 18:30:10"),
-                    String::from("Upgraded")
-                )
+                Some(String::from("web1")),
+                Some(String::from("web2")),
+                Some(String::from("web1"))
             ]
+        );
+        fs::remove_file(file1.path().unwrap()).unwrap();
+        fs::remove_file(file2.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_read_all_histories() {
+        let web1 = uuid::Uuid::new_v4().to_string();
+        let mut file1 = File::create(&web1).unwrap();
+        writeln!(
+            file1,
+            "[2019-07-14 21:33] [ALPM] installed feh (3.1.3-1)\n\
+             [2019-07-16 21:33] [ALPM] upgraded feh (3.1.3-1 -> 3.2-1)\n\
+             [2019-07-16 21:34] [ALPM] installed libev (4.27-1)\n\
+             [2019-07-17 21:34] [ALPM] removed libev (4.27-1)"
         )
+        .unwrap();
+
+        let mut config = Config::new();
+        config.hosts = vec![format!("web1={}", web1).parse().unwrap()];
+        config.limit = Some(1);
+        config.actions = vec![Action::Installed];
+        config.filters = vec![regex::Regex::new("^feh$").unwrap()];
+        let package_histories = all_histories(&read_hosts(&config).unwrap(), &config);
+
+        assert_eq!(package_histories.len(), 1);
+        assert_eq!(package_histories[0].p, "feh");
+        assert_eq!(package_histories[0].e.len(), 2);
+
+        config.filters = Vec::new();
+        let package_histories = all_histories(&read_hosts(&config).unwrap(), &config);
+        assert_eq!(package_histories.len(), 2);
+        fs::remove_file(file1.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_set_durations() {
+        let pacman_events: Vec<PacmanEvent> = vec![
+            "[2019-07-01T12:00:00+0000] [ALPM] installed feh (3.1-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-11T12:00:00+0000] [ALPM] upgraded feh (3.1-1 -> 3.2-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-12T12:00:00+0000] [ALPM] removed feh (3.2-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-20T12:00:00+0000] [ALPM] installed feh (3.2-1)"
+                .parse()
+                .unwrap(),
+        ];
+        let all: Vec<&PacmanEvent> = pacman_events.iter().collect();
+        // only the last two events are shown, e.g. using --limit 2
+        let mut package_history = PackageHistory::from_pacman_events(all[2..].to_vec());
+        let now = DateTime::parse_from_rfc3339("2019-07-21T00:00:00+00:00").unwrap();
+
+        set_durations(&mut package_history, &all, now);
+
+        let durations: Vec<Option<Duration>> =
+            package_history.e.iter().map(|e| e.duration).collect();
+        assert_eq!(durations, vec![None, Some(Duration::hours(12))]);
+        assert_eq!(
+            package_history.lifetime,
+            Some(Duration::days(11) + Duration::hours(12))
+        );
+        let json = serde_json::to_string(&package_history).unwrap();
+        assert!(json.ends_with(",\"du\":43200}],\"lt\":993600}"))
     }
 
     #[test]
@@ -195,7 +660,22 @@ mod tests {
         config.logfile = file_name;
 
         let result = run(config);
-        assert_eq!(result.is_ok(), true);
+        assert!(result.is_ok());
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_fail_without_matches() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "[2019-07-14 21:33] [ALPM] installed feh (3.1.3-1)").unwrap();
+
+        let mut config = Config::new();
+        config.logfile = file_name;
+        config.filters = vec![regex::Regex::new("^linux$").unwrap()];
+
+        let result = run(config);
+        assert_eq!(result, Err(Error::new(ErrorDetail::NoMatches)));
         fs::remove_file(file.path().unwrap()).unwrap()
     }
 }
@@ -0,0 +1,255 @@
+use chrono::NaiveDateTime;
+use regex::Regex;
+
+use crate::error::Error;
+use crate::opt::{ColorMode, Config, Direction, Host, Palette};
+use crate::pacman::action::Action;
+use crate::pacman::expression::Expression;
+use crate::pkghist::{read, PackageHistory};
+use crate::source::{OnSkippedLine, Source};
+
+/// Builds a query over a package manager's logfile without going through the command line.
+///
+/// Every setter mirrors the option of the same name, e.g. `logfile` mirrors `--logfile`.
+///
+/// ```no_run
+/// use pkghist::HistoryQuery;
+/// use regex::Regex;
+///
+/// let histories = HistoryQuery::new()
+///     .logfile("/var/log/pacman.log")
+///     .filter(Regex::new("^linux$").unwrap())
+///     .limit(3)
+///     .read()
+///     .unwrap();
+/// for history in histories {
+///     println!("{}: {} events", history.p, history.e.len());
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct HistoryQuery {
+    config: Config,
+}
+
+impl HistoryQuery {
+    pub fn new() -> HistoryQuery {
+        Default::default()
+    }
+
+    pub fn logfile(mut self, logfile: &str) -> HistoryQuery {
+        self.config.logfile = String::from(logfile);
+        self
+    }
+
+    /// Also switches to the default logfile of the source, unless another logfile was set.
+    pub fn source(mut self, source: Source) -> HistoryQuery {
+        if self.config.logfile == self.config.source.log_source().default_logfile() {
+            self.config.logfile = String::from(source.log_source().default_logfile())
+        }
+        self.config.source = source;
+        self
+    }
+
+    pub fn filter(mut self, filter: Regex) -> HistoryQuery {
+        self.config.filters.push(filter);
+        self
+    }
+
+    pub fn exclude(mut self, exclude: bool) -> HistoryQuery {
+        self.config.exclude = exclude;
+        self
+    }
+
+    pub fn with_removed(mut self, with_removed: bool) -> HistoryQuery {
+        self.config.with_removed = with_removed;
+        self
+    }
+
+    pub fn removed_only(mut self, removed_only: bool) -> HistoryQuery {
+        self.config.removed_only = removed_only;
+        self
+    }
+
+    pub fn durations(mut self, durations: bool) -> HistoryQuery {
+        self.config.durations = durations;
+        self
+    }
+
+    pub fn limit(mut self, limit: u32) -> HistoryQuery {
+        self.config.limit = Some(limit);
+        self
+    }
+
+    pub fn first(mut self, n: u32) -> HistoryQuery {
+        self.config.direction = Some(Direction::from_first(n));
+        self
+    }
+
+    pub fn last(mut self, n: u32) -> HistoryQuery {
+        self.config.direction = Some(Direction::from_last(n));
+        self
+    }
+
+    pub fn after(mut self, after: NaiveDateTime) -> HistoryQuery {
+        self.config.after = Some(after);
+        self
+    }
+
+    pub fn before(mut self, before: NaiveDateTime) -> HistoryQuery {
+        self.config.before = Some(before);
+        self
+    }
+
+    pub fn action(mut self, action: Action) -> HistoryQuery {
+        self.config.actions.push(action);
+        self
+    }
+
+    /// Can be given several times, the logfiles of the hosts replace `logfile`.
+    pub fn host(mut self, name: &str, logfile: &str) -> HistoryQuery {
+        self.config.hosts.push(Host {
+            name: String::from(name),
+            logfile: String::from(logfile),
+        });
+        self
+    }
+
+    pub fn boots(mut self, boots: &str) -> HistoryQuery {
+        self.config.boots = Some(String::from(boots));
+        self
+    }
+
+    pub fn expression(mut self, expression: Expression) -> HistoryQuery {
+        self.config.expression = Some(expression);
+        self
+    }
+
+    pub fn color(mut self, color: ColorMode) -> HistoryQuery {
+        self.config.color = color;
+        self
+    }
+
+    pub fn palette(mut self, palette: Palette) -> HistoryQuery {
+        self.config.palette = palette;
+        self
+    }
+
+    /// Called for every line of the logfiles that couldn't be read, they are ignored otherwise.
+    pub fn on_skipped_line(mut self, on_skipped_line: OnSkippedLine) -> HistoryQuery {
+        self.config.on_skipped_line = on_skipped_line;
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Reads the logfile and returns the matching package histories, ordered by their latest event.
+    pub fn read(&self) -> Result<Vec<PackageHistory>, Error> {
+        read(&self.config)
+    }
+}
+
+impl From<HistoryQuery> for Config {
+    fn from(query: HistoryQuery) -> Config {
+        query.config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::fs::File;
+    use std::io::Write;
+
+    use filepath::FilePath;
+
+    use crate::error::ErrorDetail;
+
+    use super::*;
+
+    #[test]
+    fn should_build_config() {
+        let query = HistoryQuery::new()
+            .logfile("/tmp/pacman.log")
+            .filter(Regex::new("^linux$").unwrap())
+            .exclude(true)
+            .with_removed(true)
+            .limit(2)
+            .last(10)
+            .action(Action::Upgraded);
+        let config = query.config();
+        assert_eq!(config.logfile, "/tmp/pacman.log");
+        assert_eq!(config.filters.len(), 1);
+        assert!(config.exclude);
+        assert!(config.with_removed);
+        assert_eq!(config.limit, Some(2));
+        assert_eq!(config.direction, Some(Direction::Backwards { n: 10 }));
+        assert_eq!(config.actions, vec![Action::Upgraded])
+    }
+
+    #[test]
+    fn should_build_config_of_hosts() {
+        let query = HistoryQuery::new()
+            .host("laptop", "/tmp/laptop.log")
+            .host("server", "/tmp/server.log")
+            .boots("/tmp/boots.json")
+            .expression("action=upgraded".parse().unwrap())
+            .color(ColorMode::Never);
+        let config = query.config();
+        assert_eq!(
+            config.logfiles(),
+            vec![
+                (Some("laptop"), "/tmp/laptop.log"),
+                (Some("server"), "/tmp/server.log")
+            ]
+        );
+        assert_eq!(config.boots, Some(String::from("/tmp/boots.json")));
+        assert!(config.expression.is_some());
+        assert_eq!(config.color, ColorMode::Never)
+    }
+
+    #[test]
+    fn should_use_default_logfile_of_source() {
+        let query = HistoryQuery::new().source(Source::Dpkg);
+        assert_eq!(query.config().logfile, "/var/log/dpkg.log");
+        let query = HistoryQuery::new()
+            .logfile("/tmp/dpkg.log")
+            .source(Source::Dpkg);
+        assert_eq!(query.config().logfile, "/tmp/dpkg.log")
+    }
+
+    #[test]
+    fn should_read_package_histories() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "[2019-07-14 21:33] [ALPM] installed feh (3.1.3-1)\n\
+             [2019-07-14 21:34] [ALPM] upgraded libev (4.25-1 -> 4.27-1)\n\
+             [2019-07-15 21:35] [ALPM] upgraded feh (3.1.3-1 -> 3.2-1)"
+        )
+        .unwrap();
+
+        let histories = HistoryQuery::new()
+            .logfile(&file_name)
+            .filter(Regex::new("^feh$").unwrap())
+            .read()
+            .unwrap();
+
+        assert_eq!(histories.len(), 1);
+        assert_eq!(histories[0].p, "feh");
+        assert_eq!(histories[0].e.len(), 2);
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_not_read_missing_logfile() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let result = HistoryQuery::new().logfile(&file_name).read();
+        match result.unwrap_err().detail() {
+            ErrorDetail::UnreadableLogfile { path, .. } => assert_eq!(path, &file_name),
+            detail => panic!("unexpected error {:?}", detail),
+        }
+    }
+}
@@ -0,0 +1,341 @@
+use std::fs;
+use std::io::stdout;
+
+use chrono::{DateTime, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use termion::color;
+
+use crate::error::{Error, ErrorDetail, Position};
+use crate::opt::{Config, Format};
+use crate::pkghist::kernel::is_kernel;
+use crate::pkghist::{all_histories, read_hosts, Event, PackageHistory};
+
+/// Packages that are only reloaded by a reboot, besides kernels, firmware, microcode, modules and drivers.
+const REBOOT_PACKAGES: [&str; 4] = ["glibc", "systemd", "dbus", "dbus-broker"];
+
+/// A boot as exported by `journalctl --list-boots -o json`, the current boot has the index 0.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+pub struct Boot {
+    pub index: i64,
+    pub boot_id: String,
+    /// Microseconds since the epoch
+    pub first_entry: i64,
+    #[serde(default)]
+    pub last_entry: Option<i64>,
+}
+
+impl Boot {
+    pub fn started(&self) -> DateTime<FixedOffset> {
+        let date = NaiveDateTime::from_timestamp_opt(
+            self.first_entry.div_euclid(1_000_000),
+            self.first_entry.rem_euclid(1_000_000) as u32 * 1000,
+        )
+        .unwrap_or_default();
+        Utc.from_utc_datetime(&date).into()
+    }
+}
+
+/// A package changed since the current boot, `None` if it was not installed.
+#[derive(Debug, Serialize, PartialEq)]
+struct Pending {
+    package: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    running: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installed: Option<String>,
+    date: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    host: Option<String>,
+}
+
+pub fn run(config: &Config) -> Result<(), Error> {
+    let boots = match boots_of(config)? {
+        Some(boots) => boots,
+        None => {
+            return Err(Error::new(ErrorDetail::InvalidConfig {
+                msg: String::from("The reboot report needs the boots given with --boots"),
+            }))
+        }
+    };
+    // packages removed since the boot are pending as well
+    let package_histories = all_histories(&read_hosts(config)?, config);
+    let pending = match boots.iter().max_by_key(|b| b.index) {
+        Some(boot) => pending(&package_histories, boot.started()),
+        None => Vec::new(),
+    };
+    if pending.is_empty() {
+        return Err(Error::new(ErrorDetail::NoMatches));
+    }
+    print_pending(&mut stdout(), &pending, &config.format)
+}
+
+/// Loads the boots of the config, they are read from the local journal and don't apply to other hosts.
+pub fn boots_of(config: &Config) -> Result<Option<Vec<Boot>>, Error> {
+    match &config.boots {
+        Some(_) if !config.hosts.is_empty() => Err(Error::new(ErrorDetail::InvalidConfig {
+            msg: String::from("The boots can't be read together with hosts"),
+        })),
+        Some(file) => load(file).map(Some),
+        None => Ok(None),
+    }
+}
+
+pub fn load(file: &str) -> Result<Vec<Boot>, Error> {
+    let content = fs::read_to_string(file).map_err(|e| {
+        Error::new(ErrorDetail::UnreadableLogfile {
+            path: String::from(file),
+            msg: e.to_string(),
+        })
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        Error::new(ErrorDetail::InvalidLogfile {
+            at: Position {
+                path: String::from(file),
+                line: Some(e.line()),
+            },
+            msg: e.to_string(),
+        })
+    })
+}
+
+/// The index of the boot the date belongs to, `None` if it is older than the first boot.
+pub fn boot_index(boots: &[Boot], date: &DateTime<FixedOffset>) -> Option<i64> {
+    boots
+        .iter()
+        .filter(|b| b.started() <= *date)
+        .max_by_key(|b| b.started())
+        .map(|b| b.index)
+}
+
+fn needs_reboot(package: &str) -> bool {
+    is_kernel(package)
+        || package.starts_with("linux-firmware")
+        || package.starts_with("nvidia")
+        || package.ends_with("-dkms")
+        || package.ends_with("-ucode")
+        || REBOOT_PACKAGES.contains(&package)
+}
+
+/*
+ * The running version is the last one installed before the boot, packages changed back
+ * to the running version don't need a reboot.
+ */
+fn pending(package_histories: &[PackageHistory], booted: DateTime<FixedOffset>) -> Vec<Pending> {
+    let mut pending = Vec::new();
+    for package_history in package_histories.iter().filter(|p| needs_reboot(&p.p)) {
+        for (host, history) in package_history.split_hosts() {
+            let (before, after): (Vec<_>, Vec<_>) = history.e.iter().partition(|e| e.date < booted);
+            let last = match after.last() {
+                Some(last) => last,
+                None => continue,
+            };
+            let version = |e: &&Event| -> Option<String> {
+                Some(e.version.to_string()).filter(|_| e.action.is_installed())
+            };
+            let running = before.last().and_then(version);
+            let installed = version(last);
+            if running != installed {
+                pending.push(Pending {
+                    package: package_history.p.clone(),
+                    running,
+                    installed,
+                    date: last.printable_date(),
+                    host: host.map(String::from),
+                })
+            }
+        }
+    }
+    pending
+}
+
+fn print_pending<W: std::io::Write>(
+    stdout: &mut W,
+    pending: &[Pending],
+    format: &Format,
+) -> Result<(), Error> {
+    if let Format::Json { .. } = format {
+        writeln!(stdout, "{}", serde_json::to_string_pretty(pending).unwrap())?;
+        return Ok(());
+    }
+    let with_colors = format.with_colors();
+    let p_max = pending.iter().map(|p| p.package.len()).max().unwrap_or(0);
+    for p in pending {
+        if with_colors {
+            write!(stdout, "{yellow}", yellow = color::Fg(color::Yellow))?
+        }
+        write!(
+            stdout,
+            "{package: <p_max$}  {running} -> {installed} since {date}",
+            package = p.package,
+            p_max = p_max,
+            running = p.running.as_deref().unwrap_or("not installed"),
+            installed = p.installed.as_deref().unwrap_or("removed"),
+            date = p.date
+        )?;
+        if let Some(host) = &p.host {
+            write!(stdout, " on {}", host)?
+        }
+        if with_colors {
+            writeln!(stdout, "{reset}", reset = color::Fg(color::Reset))?
+        } else {
+            writeln!(stdout)?
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write;
+
+    use filepath::FilePath;
+
+    use crate::pacman::action::Action;
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    fn boots() -> Vec<Boot> {
+        vec![
+            Boot {
+                index: -1,
+                boot_id: String::from("5b0a3c8e"),
+                // 2024-01-10 08:00:00 UTC
+                first_entry: 1_704_873_600_000_000,
+                last_entry: Some(1_705_305_600_000_000),
+            },
+            Boot {
+                index: 0,
+                boot_id: String::from("9f1e2d7c"),
+                // 2024-01-15 08:00:00 UTC
+                first_entry: 1_705_305_600_000_000,
+                last_entry: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn should_load_boots() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "[{{\"index\":-1,\"boot_id\":\"5b0a3c8e\",\"first_entry\":1704873600000000,\"last_entry\":1705305600000000}},\
+             {{\"index\":0,\"boot_id\":\"9f1e2d7c\",\"first_entry\":1705305600000000}}]"
+        )
+        .unwrap();
+
+        let loaded = load(&file_name).unwrap();
+
+        assert_eq!(loaded, boots());
+        assert_eq!(
+            loaded[1].started().to_rfc3339(),
+            "2024-01-15T08:00:00+00:00"
+        );
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_report_line_of_invalid_boots() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(file, "[\n{{\"index\":-1,}}\n]").unwrap();
+
+        let result = load(&file_name);
+
+        match result.unwrap_err().detail() {
+            ErrorDetail::InvalidLogfile { at, .. } => {
+                assert_eq!(at.path, file_name);
+                assert_eq!(at.line, Some(2))
+            }
+            detail => panic!("unexpected error {:?}", detail),
+        }
+        fs::remove_file(file.path().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn should_not_read_boots_with_hosts() {
+        let mut config = Config::new();
+        config.boots = Some(String::from("boots.json"));
+        config.hosts = vec!["web1=/tmp/web1.log".parse().unwrap()];
+
+        let result = boots_of(&config);
+
+        assert_eq!(result.unwrap_err().exit_code(), 3)
+    }
+
+    #[test]
+    fn should_find_boot_index() {
+        let boots = boots();
+        let date = |s: &str| event("1.0-1", s, Action::Installed).date;
+
+        assert_eq!(boot_index(&boots, &date("2024-01-01 12:00:00")), None);
+        assert_eq!(boot_index(&boots, &date("2024-01-12 12:00:00")), Some(-1));
+        assert_eq!(boot_index(&boots, &date("2024-01-15 12:00:00")), Some(0))
+    }
+
+    #[test]
+    fn should_find_pending_reboots() {
+        let package_histories = vec![
+            PackageHistory {
+                p: String::from("linux"),
+                e: vec![
+                    event("6.6.9-1", "2024-01-10 12:00:00", Action::Installed),
+                    event("6.7.1-1", "2024-01-15 12:00:00", Action::Upgraded),
+                ],
+                lifetime: None,
+            },
+            PackageHistory {
+                p: String::from("glibc"),
+                e: vec![
+                    event("2.38-7", "2024-01-10 12:00:00", Action::Installed),
+                    event("2.39-1", "2024-01-15 12:00:00", Action::Upgraded),
+                    event("2.38-7", "2024-01-15 13:00:00", Action::Downgraded),
+                ],
+                lifetime: None,
+            },
+            PackageHistory {
+                p: String::from("vim"),
+                e: vec![event("9.1-1", "2024-01-15 12:00:00", Action::Installed)],
+                lifetime: None,
+            },
+        ];
+
+        let pending = pending(&package_histories, boots()[1].started());
+
+        assert_eq!(
+            pending,
+            vec![Pending {
+                package: String::from("linux"),
+                running: Some(String::from("6.6.9-1")),
+                installed: Some(String::from("6.7.1-1")),
+                date: String::from("2024-01-15 12:00:00"),
+                host: None,
+            }]
+        )
+    }
+
+    #[test]
+    fn should_print_pending_no_colors() {
+        let pending = vec![Pending {
+            package: String::from("linux"),
+            running: Some(String::from("6.6.9-1")),
+            installed: None,
+            date: String::from("2024-01-15 12:00:00"),
+            host: None,
+        }];
+        let mut stdout = Vec::new();
+        print_pending(
+            &mut stdout,
+            &pending,
+            &Format::Plain {
+                with_colors: false,
+                without_details: false,
+            },
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(str, "linux  6.6.9-1 -> removed since 2024-01-15 12:00:00\n")
+    }
+}
@@ -0,0 +1,367 @@
+use crate::error::Error;
+use crate::pacman::action::Action;
+use crate::pkghist::{Event, PackageHistory};
+
+const ACTIONS: [Action; 5] = [
+    Action::Installed,
+    Action::Reinstalled,
+    Action::Upgraded,
+    Action::Downgraded,
+    Action::Removed,
+];
+
+struct Summary {
+    packages: usize,
+    events: usize,
+    first: Option<String>,
+    last: Option<String>,
+    actions: Vec<(Action, usize)>,
+}
+
+impl Summary {
+    fn from_package_histories(package_histories: &[PackageHistory]) -> Summary {
+        let events: Vec<&Event> = package_histories.iter().flat_map(|p| &p.e).collect();
+        let actions = ACTIONS
+            .iter()
+            .map(|action| {
+                let count = package_histories
+                    .iter()
+                    .flat_map(|p| p.e.iter())
+                    .filter(|e| e.action == *action)
+                    .count();
+                (action.clone(), count)
+            })
+            .collect();
+        Summary {
+            packages: package_histories.len(),
+            events: events.len(),
+            first: events
+                .iter()
+                .min_by_key(|e| e.date)
+                .map(|e| e.printable_date()),
+            last: events
+                .iter()
+                .max_by_key(|e| e.date)
+                .map(|e| e.printable_date()),
+            actions,
+        }
+    }
+
+    fn period(&self) -> String {
+        match (&self.first, &self.last) {
+            (Some(first), Some(last)) => format!("{} - {}", first, last),
+            _ => String::from("-"),
+        }
+    }
+}
+
+/*
+ * Same scheme as the colored plain output: removals are red, downgrades yellow, everything else green
+ */
+fn marker(action: &Action) -> &'static str {
+    match action {
+        Action::Removed => "🔴",
+        Action::Downgraded => "🟡",
+        _ => "🟢",
+    }
+}
+
+fn css_class(action: &Action) -> &'static str {
+    match action {
+        Action::Removed => "removed",
+        Action::Downgraded => "downgraded",
+        _ => "installed",
+    }
+}
+
+fn last_action(package_history: &PackageHistory) -> Action {
+    match package_history.e.last() {
+        Some(event) => event.action.clone(),
+        None => Action::Installed,
+    }
+}
+
+fn escape_markdown(s: &str) -> String {
+    s.replace('|', "\\|")
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn format_markdown<W: std::io::Write>(
+    stdout: &mut W,
+    package_histories: &[PackageHistory],
+    without_details: bool,
+) -> Result<(), Error> {
+    let summary = Summary::from_package_histories(package_histories);
+    writeln!(stdout, "# Package history")?;
+    writeln!(stdout)?;
+    writeln!(stdout, "| Packages | Events | Period |")?;
+    writeln!(stdout, "|----------|--------|--------|")?;
+    writeln!(
+        stdout,
+        "| {} | {} | {} |",
+        summary.packages,
+        summary.events,
+        summary.period()
+    )?;
+    writeln!(stdout)?;
+    writeln!(stdout, "| Action | Events |")?;
+    writeln!(stdout, "|--------|--------|")?;
+    for (action, count) in &summary.actions {
+        writeln!(stdout, "| {} {} | {} |", marker(action), action, count)?
+    }
+
+    if without_details {
+        writeln!(stdout)?;
+        for package_history in package_histories {
+            writeln!(
+                stdout,
+                "- {} {}",
+                marker(&last_action(package_history)),
+                escape_markdown(&package_history.p)
+            )?
+        }
+        return Ok(());
+    }
+
+    let with_hosts = package_histories.iter().any(|p| p.has_hosts());
+    for package_history in package_histories {
+        writeln!(stdout)?;
+        writeln!(
+            stdout,
+            "## {} {}",
+            marker(&last_action(package_history)),
+            escape_markdown(&package_history.p)
+        )?;
+        writeln!(stdout)?;
+        if with_hosts {
+            writeln!(stdout, "| Date | Host | Action | Version |")?;
+            writeln!(stdout, "|------|------|--------|---------|")?;
+        } else {
+            writeln!(stdout, "| Date | Action | Version |")?;
+            writeln!(stdout, "|------|--------|---------|")?;
+        }
+        for event in &package_history.e {
+            let action = &event.action;
+            let host = match (with_hosts, &event.host) {
+                (true, Some(host)) => format!(" {} |", escape_markdown(host)),
+                (true, None) => String::from("  |"),
+                (false, _) => String::new(),
+            };
+            writeln!(
+                stdout,
+                "| {} |{} {} {} | {} |",
+                event.printable_date(),
+                host,
+                marker(action),
+                action,
+                escape_markdown(&event.version.to_string())
+            )?
+        }
+    }
+    Ok(())
+}
+
+pub fn format_html<W: std::io::Write>(
+    stdout: &mut W,
+    package_histories: &[PackageHistory],
+    without_details: bool,
+) -> Result<(), Error> {
+    let summary = Summary::from_package_histories(package_histories);
+    writeln!(stdout, "<!DOCTYPE html>")?;
+    writeln!(stdout, "<html>")?;
+    writeln!(stdout, "<head>")?;
+    writeln!(stdout, "<meta charset=\"utf-8\">")?;
+    writeln!(stdout, "<title>Package history</title>")?;
+    writeln!(stdout, "<style>")?;
+    writeln!(
+        stdout,
+        "body {{ font-family: sans-serif; }}\n\
+         table {{ border-collapse: collapse; margin-bottom: 1em; }}\n\
+         th, td {{ border: 1px solid #ccc; padding: 2px 8px; text-align: left; }}\n\
+         .installed {{ color: green; }}\n\
+         .downgraded {{ color: olive; }}\n\
+         .removed {{ color: red; }}"
+    )?;
+    writeln!(stdout, "</style>")?;
+    writeln!(stdout, "</head>")?;
+    writeln!(stdout, "<body>")?;
+    writeln!(stdout, "<h1>Package history</h1>")?;
+    writeln!(stdout, "<table>")?;
+    writeln!(
+        stdout,
+        "<tr><th>Packages</th><th>Events</th><th>Period</th></tr>"
+    )?;
+    writeln!(
+        stdout,
+        "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+        summary.packages,
+        summary.events,
+        summary.period()
+    )?;
+    writeln!(stdout, "</table>")?;
+    writeln!(stdout, "<table>")?;
+    writeln!(stdout, "<tr><th>Action</th><th>Events</th></tr>")?;
+    for (action, count) in &summary.actions {
+        writeln!(
+            stdout,
+            "<tr><td class=\"{}\">{}</td><td>{}</td></tr>",
+            css_class(action),
+            action,
+            count
+        )?
+    }
+    writeln!(stdout, "</table>")?;
+
+    if without_details {
+        writeln!(stdout, "<ul>")?;
+        for package_history in package_histories {
+            writeln!(
+                stdout,
+                "<li class=\"{}\">{}</li>",
+                css_class(&last_action(package_history)),
+                escape_html(&package_history.p)
+            )?
+        }
+        writeln!(stdout, "</ul>")?;
+    } else {
+        let with_hosts = package_histories.iter().any(|p| p.has_hosts());
+        for package_history in package_histories {
+            writeln!(
+                stdout,
+                "<h2 class=\"{}\">{}</h2>",
+                css_class(&last_action(package_history)),
+                escape_html(&package_history.p)
+            )?;
+            writeln!(stdout, "<table>")?;
+            if with_hosts {
+                writeln!(
+                    stdout,
+                    "<tr><th>Date</th><th>Host</th><th>Action</th><th>Version</th></tr>"
+                )?
+            } else {
+                writeln!(
+                    stdout,
+                    "<tr><th>Date</th><th>Action</th><th>Version</th></tr>"
+                )?
+            }
+            for event in &package_history.e {
+                let action = &event.action;
+                let host = match (with_hosts, &event.host) {
+                    (true, Some(host)) => format!("<td>{}</td>", escape_html(host)),
+                    (true, None) => String::from("<td></td>"),
+                    (false, _) => String::new(),
+                };
+                writeln!(
+                    stdout,
+                    "<tr class=\"{}\"><td>{}</td>{}<td>{}</td><td>{}</td></tr>",
+                    css_class(action),
+                    event.printable_date(),
+                    host,
+                    action,
+                    escape_html(&event.version.to_string())
+                )?
+            }
+            writeln!(stdout, "</table>")?;
+        }
+    }
+    writeln!(stdout, "</body>")?;
+    writeln!(stdout, "</html>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    fn some_package_histories() -> Vec<PackageHistory> {
+        vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![
+                event("0.0.2", "2019-08-26 12:00:00", Action::Upgraded),
+                event("0.0.1", "2019-08-26 13:00:00", Action::Downgraded),
+                event("0.0.1", "2019-08-26 14:00:00", Action::Removed),
+            ],
+            lifetime: None,
+        }]
+    }
+
+    #[test]
+    fn should_print_markdown() {
+        let mut stdout = Vec::new();
+        format_markdown(&mut stdout, &some_package_histories(), false).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "# Package history\n\n\
+             | Packages | Events | Period |\n\
+             |----------|--------|--------|\n\
+             | 1 | 3 | 2019-08-26 12:00:00 - 2019-08-26 14:00:00 |\n\n\
+             | Action | Events |\n\
+             |--------|--------|\n\
+             | 🟢 Installed | 0 |\n\
+             | 🟢 Reinstalled | 0 |\n\
+             | 🟢 Upgraded | 1 |\n\
+             | 🟡 Downgraded | 1 |\n\
+             | 🔴 Removed | 1 |\n\n\
+             ## 🔴 foo\n\n\
+             | Date | Action | Version |\n\
+             |------|--------|---------|\n\
+             | 2019-08-26 12:00:00 | 🟢 Upgraded | 0.0.2 |\n\
+             | 2019-08-26 13:00:00 | 🟡 Downgraded | 0.0.1 |\n\
+             | 2019-08-26 14:00:00 | 🔴 Removed | 0.0.1 |\n"
+        )
+    }
+
+    #[test]
+    fn should_print_markdown_no_details() {
+        let mut stdout = Vec::new();
+        format_markdown(&mut stdout, &some_package_histories(), true).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert!(str.ends_with("| 🔴 Removed | 1 |\n\n- 🔴 foo\n"))
+    }
+
+    #[test]
+    fn should_print_html() {
+        let mut stdout = Vec::new();
+        format_html(&mut stdout, &some_package_histories(), false).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert!(str.starts_with("<!DOCTYPE html>\n<html>\n"));
+        assert!(str.contains(
+            "<tr><td>1</td><td>3</td><td>2019-08-26 12:00:00 - 2019-08-26 14:00:00</td></tr>"
+        ));
+        assert!(str.contains("<h2 class=\"removed\">foo</h2>"));
+        assert!(str.contains(
+            "<tr class=\"downgraded\"><td>2019-08-26 13:00:00</td><td>Downgraded</td><td>0.0.1</td></tr>"
+        ));
+        assert!(str.ends_with("</body>\n</html>\n"))
+    }
+
+    #[test]
+    fn should_print_hosts() {
+        let mut package_histories = some_package_histories();
+        for event in package_histories[0].e.iter_mut() {
+            event.host = Some(String::from("web1"))
+        }
+        let mut stdout = Vec::new();
+        format_markdown(&mut stdout, &package_histories, false).unwrap();
+        format_html(&mut stdout, &package_histories, false).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert!(str.contains("| 2019-08-26 13:00:00 | web1 | 🟡 Downgraded | 0.0.1 |\n"));
+        assert!(str.contains(
+            "<tr class=\"downgraded\"><td>2019-08-26 13:00:00</td><td>web1</td><td>Downgraded</td><td>0.0.1</td></tr>"
+        ))
+    }
+
+    #[test]
+    fn should_escape_html() {
+        assert_eq!(escape_html("gtk<3>&\""), "gtk&lt;3&gt;&amp;&quot;")
+    }
+}
@@ -0,0 +1,278 @@
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorDetail};
+use crate::pacman::action::Action;
+use crate::pkghist::{Event, PackageHistory};
+
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+enum Field {
+    Package,
+    Date,
+    Action,
+    Version,
+    From,
+    To,
+    Host,
+}
+
+impl FromStr for Field {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "package" => Ok(Field::Package),
+            "date" => Ok(Field::Date),
+            "action" => Ok(Field::Action),
+            "version" => Ok(Field::Version),
+            "from" => Ok(Field::From),
+            "to" => Ok(Field::To),
+            "host" => Ok(Field::Host),
+            _ => Err(invalid_template(format!("Unknown field '{}'", s))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+enum Part {
+    Text(String),
+    Field(Field),
+    Section {
+        field: Field,
+        inverted: bool,
+        parts: Vec<Part>,
+    },
+}
+
+/// A template rendered once per event, e.g. `{date} {action} {package}{#from} {from}{/from}{#to} -> {to}{/to}`.
+///
+/// `{#field}...{/field}` is only rendered if the field is not empty, `{^field}...{/field}` only if it is.
+/// Use `{{` and `}}` for literal braces.
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+// an open section (or the template itself) with the parts collected so far
+type Level = (Option<(Field, bool)>, Vec<Part>);
+
+fn invalid_template(msg: String) -> Error {
+    Error::new(ErrorDetail::InvalidTemplate { msg })
+}
+
+impl FromStr for Template {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the innermost open section is on top, the template itself at the bottom
+        let mut stack: Vec<Level> = vec![(None, Vec::new())];
+        let mut text = String::new();
+        let mut chars = s.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{')
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}')
+                }
+                '{' => {
+                    let mut tag = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => tag.push(c),
+                            None => return Err(invalid_template(format!("Unclosed '{{{}'", tag))),
+                        }
+                    }
+                    let parts = &mut stack.last_mut().unwrap().1;
+                    if !text.is_empty() {
+                        parts.push(Part::Text(text.clone()));
+                        text.clear()
+                    }
+                    if let Some(name) = tag.strip_prefix('#') {
+                        stack.push((Some((name.parse()?, false)), Vec::new()))
+                    } else if let Some(name) = tag.strip_prefix('^') {
+                        stack.push((Some((name.parse()?, true)), Vec::new()))
+                    } else if let Some(name) = tag.strip_prefix('/') {
+                        let closing: Field = name.parse()?;
+                        match stack.pop() {
+                            Some((Some((field, inverted)), parts)) if field == closing => {
+                                stack.last_mut().unwrap().1.push(Part::Section {
+                                    field,
+                                    inverted,
+                                    parts,
+                                })
+                            }
+                            _ => {
+                                return Err(invalid_template(format!("Unexpected '{{/{}}}'", name)))
+                            }
+                        }
+                    } else {
+                        parts.push(Part::Field(tag.parse()?))
+                    }
+                }
+                c => text.push(c),
+            }
+        }
+
+        if stack.len() > 1 {
+            return Err(invalid_template(String::from("Unclosed section")));
+        }
+        let mut parts = stack.pop().unwrap().1;
+        if !text.is_empty() {
+            parts.push(Part::Text(text))
+        }
+        Ok(Template { parts })
+    }
+}
+
+impl Template {
+    fn render(&self, package: &str, event: &Event) -> String {
+        let mut out = String::new();
+        render_parts(&mut out, &self.parts, package, event);
+        out
+    }
+}
+
+fn render_parts(out: &mut String, parts: &[Part], package: &str, event: &Event) {
+    for part in parts {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Field(field) => out.push_str(&value(field, package, event)),
+            Part::Section {
+                field,
+                inverted,
+                parts,
+            } => {
+                if value(field, package, event).is_empty() == *inverted {
+                    render_parts(out, parts, package, event)
+                }
+            }
+        }
+    }
+}
+
+/*
+ * Removals have no 'to', installs no 'from'
+ */
+fn value(field: &Field, package: &str, event: &Event) -> String {
+    match field {
+        Field::Package => String::from(package),
+        Field::Date => event.printable_date(),
+        Field::Action => event.action.to_string(),
+        Field::Version => event.version.to_string(),
+        Field::From => match (&event.action, &event.from) {
+            (Action::Removed, _) => event.version.to_string(),
+            (_, Some(from)) => from.to_string(),
+            (_, None) => String::new(),
+        },
+        Field::To => match event.action {
+            Action::Removed => String::new(),
+            _ => event.version.to_string(),
+        },
+        Field::Host => event.host.clone().unwrap_or_default(),
+    }
+}
+
+pub fn format_template<W: std::io::Write>(
+    stdout: &mut W,
+    package_histories: &[PackageHistory],
+    template: &Template,
+) -> Result<(), Error> {
+    for package_history in package_histories {
+        for event in &package_history.e {
+            writeln!(stdout, "{}", template.render(&package_history.p, event))?
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    fn some_package_histories() -> Vec<PackageHistory> {
+        vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![
+                event("0.0.1", "2019-08-26 12:00:00", Action::Installed),
+                Event {
+                    from: Some("0.0.1".parse().unwrap()),
+                    ..event("0.0.2", "2019-08-26 13:00:00", Action::Upgraded)
+                },
+                event("0.0.2", "2019-08-26 14:00:00", Action::Removed),
+            ],
+            lifetime: None,
+        }]
+    }
+
+    #[test]
+    fn should_print_template() {
+        let template: Template = "{date} {action} {package} {from} -> {to}".parse().unwrap();
+        let mut stdout = Vec::new();
+        format_template(&mut stdout, &some_package_histories(), &template).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "2019-08-26 12:00:00 Installed foo  -> 0.0.1\n\
+             2019-08-26 13:00:00 Upgraded foo 0.0.1 -> 0.0.2\n\
+             2019-08-26 14:00:00 Removed foo 0.0.2 -> \n"
+        )
+    }
+
+    #[test]
+    fn should_print_template_with_sections() {
+        let template: Template =
+            "{{{package}}}{#from} {from}{/from}{#to} -> {to}{/to}{^to} (gone){/to}"
+                .parse()
+                .unwrap();
+        let mut stdout = Vec::new();
+        format_template(&mut stdout, &some_package_histories(), &template).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "{foo} -> 0.0.1\n{foo} 0.0.1 -> 0.0.2\n{foo} 0.0.2 (gone)\n"
+        )
+    }
+
+    #[test]
+    fn should_print_host() {
+        let template: Template = "{package}{#host}@{host}{/host}".parse().unwrap();
+        let mut package_histories = some_package_histories();
+        package_histories[0].e[0].host = Some(String::from("web1"));
+        let mut stdout = Vec::new();
+        format_template(&mut stdout, &package_histories, &template).unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(str, "foo@web1\nfoo\nfoo\n")
+    }
+
+    #[test]
+    fn should_not_parse_unknown_field() {
+        let template: Result<Template, Error> = "{foo}".parse();
+        assert_eq!(
+            template.unwrap_err(),
+            Error::new(ErrorDetail::InvalidTemplate {
+                msg: String::from("Unknown field 'foo'")
+            })
+        )
+    }
+
+    #[test]
+    fn should_not_parse_unclosed_tag() {
+        let template: Result<Template, Error> = "{date".parse();
+        assert!(template.is_err())
+    }
+
+    #[test]
+    fn should_not_parse_unbalanced_sections() {
+        let unclosed: Result<Template, Error> = "{#from}{from}".parse();
+        assert!(unclosed.is_err());
+        let mismatched: Result<Template, Error> = "{#from}{from}{/to}".parse();
+        assert!(mismatched.is_err())
+    }
+}
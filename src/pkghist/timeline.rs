@@ -0,0 +1,229 @@
+use chrono::NaiveDateTime;
+use termion::color;
+
+use crate::error::Error;
+use crate::opt::Palette;
+use crate::pacman::action::Action;
+use crate::pkghist::format::action_color;
+use crate::pkghist::PackageHistory;
+
+pub fn format_timeline<W: std::io::Write>(
+    stdout: &mut W,
+    package_histories: &[PackageHistory],
+    with_colors: bool,
+    width: usize,
+    palette: &Palette,
+) -> Result<(), Error> {
+    let dates: Vec<NaiveDateTime> = package_histories
+        .iter()
+        .flat_map(|p| p.e.iter())
+        .map(|e| e.date.naive_local())
+        .collect();
+    let (first, last) = match (dates.iter().min(), dates.iter().max()) {
+        (Some(first), Some(last)) => (*first, *last),
+        _ => return Ok(()),
+    };
+
+    // one row per package and host, e.g. linux@web1
+    let rows: Vec<(String, PackageHistory)> = package_histories
+        .iter()
+        .flat_map(|p| p.split_hosts())
+        .map(|(host, package_history)| match host {
+            Some(host) => (format!("{}@{}", package_history.p, host), package_history),
+            None => (package_history.p.clone(), package_history),
+        })
+        .collect();
+    let p_max = rows
+        .iter()
+        .map(|(label, _)| label.chars().count())
+        .max()
+        .unwrap()
+        .min(width / 3);
+    let bar_width = width.saturating_sub(p_max + 3).max(10);
+    let span = (last - first).num_seconds().max(1);
+    let column = |date: &NaiveDateTime| -> usize {
+        ((*date - first).num_seconds() * (bar_width as i64 - 1) / span) as usize
+    };
+
+    for (label, package_history) in &rows {
+        let bar = timeline(package_history, bar_width, &column);
+        let package: String = label.chars().take(p_max).collect();
+        write!(
+            stdout,
+            "{package: <p_max$} |",
+            package = package,
+            p_max = p_max
+        )?;
+        for (c, action) in bar {
+            match (with_colors, action) {
+                (true, Some(action)) => write!(
+                    stdout,
+                    "{color}{c}{reset}",
+                    color = action_color(palette, &action),
+                    reset = color::Fg(color::Reset)
+                )?,
+                _ => write!(stdout, "{c}")?,
+            }
+        }
+        writeln!(stdout, "|")?
+    }
+
+    let from = first.format("%Y-%m-%d").to_string();
+    let to = last.format("%Y-%m-%d").to_string();
+    writeln!(
+        stdout,
+        "{empty: <p_max$}  {from}{to: >gap$}",
+        empty = "",
+        p_max = p_max,
+        from = from,
+        to = to,
+        gap = bar_width.saturating_sub(from.len()).max(to.len() + 1)
+    )?;
+    writeln!(
+        stdout,
+        "I installed, R reinstalled, U upgraded, D downgraded, X removed, =/- installed versions"
+    )?;
+    Ok(())
+}
+
+/*
+ * Every version is drawn as a segment of '=' or '-' (alternating) starting at its event,
+ * the events themselves are drawn on top of them.
+ */
+fn timeline(
+    package_history: &PackageHistory,
+    bar_width: usize,
+    column: &dyn Fn(&NaiveDateTime) -> usize,
+) -> Vec<(char, Option<Action>)> {
+    let mut bar = vec![(' ', None); bar_width];
+    let events: Vec<(usize, Action)> = package_history
+        .e
+        .iter()
+        .map(|e| (column(&e.date.naive_local()), e.action.clone()))
+        .collect();
+
+    for (idx, (start, action)) in events.iter().enumerate() {
+        if action.is_installed() {
+            let end = match events.get(idx + 1) {
+                Some((next, _)) => *next,
+                None => bar_width,
+            };
+            let fill = if idx % 2 == 0 { '=' } else { '-' };
+            for cell in bar.iter_mut().take(end).skip(*start) {
+                *cell = (fill, None)
+            }
+        }
+    }
+    for (column, action) in events {
+        let mark = match action {
+            Action::Installed => 'I',
+            Action::Reinstalled => 'R',
+            Action::Upgraded => 'U',
+            Action::Downgraded => 'D',
+            Action::Removed => 'X',
+        };
+        bar[column] = (mark, Some(action))
+    }
+    bar
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pkghist::tests::event;
+
+    use super::*;
+
+    fn some_package_histories() -> Vec<PackageHistory> {
+        vec![
+            PackageHistory {
+                p: String::from("foo"),
+                e: vec![
+                    event("0.0.1", "2019-08-01 00:00:00", Action::Installed),
+                    event("0.0.2", "2019-08-05 00:00:00", Action::Upgraded),
+                    event("0.0.2", "2019-08-08 00:00:00", Action::Removed),
+                ],
+                lifetime: None,
+            },
+            PackageHistory {
+                p: String::from("barbaz"),
+                e: vec![
+                    event("1.0.0", "2019-08-03 00:00:00", Action::Installed),
+                    event("0.9.0", "2019-08-11 00:00:00", Action::Downgraded),
+                ],
+                lifetime: None,
+            },
+        ]
+    }
+
+    #[test]
+    fn should_print_timeline_no_colors() {
+        let mut stdout = Vec::new();
+        format_timeline(
+            &mut stdout,
+            &some_package_histories(),
+            false,
+            40,
+            &Palette::default(),
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert_eq!(
+            str,
+            "foo    |I===========U--------X         |\n\
+             barbaz |      I=======================D|\n\
+             \x20       2019-08-01           2019-08-11\n\
+             I installed, R reinstalled, U upgraded, D downgraded, X removed, =/- installed versions\n"
+        )
+    }
+
+    #[test]
+    fn should_print_timeline_colored() {
+        let mut stdout = Vec::new();
+        format_timeline(
+            &mut stdout,
+            &some_package_histories(),
+            true,
+            20,
+            &Palette::default(),
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert!(str.starts_with("foo    |\u{1b}[38;5;2mI\u{1b}[39m===\u{1b}[38;5;2mU"));
+        assert!(str.contains("\u{1b}[38;5;1mX\u{1b}[39m"));
+        assert!(str.contains("\u{1b}[38;5;3mD\u{1b}[39m"))
+    }
+
+    #[test]
+    fn should_print_a_row_per_host() {
+        let mut ev1 = event("0.0.1", "2019-08-01 00:00:00", Action::Installed);
+        ev1.host = Some(String::from("web1"));
+        let mut ev2 = event("0.0.1", "2019-08-11 00:00:00", Action::Installed);
+        ev2.host = Some(String::from("web2"));
+        let package_histories = vec![PackageHistory {
+            p: String::from("foo"),
+            e: vec![ev1, ev2],
+            lifetime: None,
+        }];
+        let mut stdout = Vec::new();
+        format_timeline(
+            &mut stdout,
+            &package_histories,
+            false,
+            40,
+            &Palette::default(),
+        )
+        .unwrap();
+        let str = String::from_utf8(stdout).unwrap();
+        assert!(str.starts_with(
+            "foo@web1 |I============================|\n\
+             foo@web2 |                            I|\n"
+        ))
+    }
+
+    #[test]
+    fn should_print_nothing_without_events() {
+        let mut stdout = Vec::new();
+        format_timeline(&mut stdout, &[], true, 80, &Palette::default()).unwrap();
+        assert!(stdout.is_empty())
+    }
+}
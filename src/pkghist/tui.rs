@@ -0,0 +1,406 @@
+use std::io::{stdin, stdout, Write};
+
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::screen::IntoAlternateScreen;
+use termion::{clear, color, cursor, style, terminal_size};
+
+use crate::error::Error;
+use crate::opt::Config;
+use crate::pkghist::{host_histories, read_hosts, HostEvents, PackageHistory};
+
+pub fn run(config: Config) -> Result<(), Error> {
+    let host_events = read_hosts(&config)?;
+
+    let mut browser = Browser::new(host_events, config);
+    let mut screen = Screen::new(stdout().into_raw_mode()?.into_alternate_screen()?)?;
+    let (width, height) = terminal_size()?;
+    browser.render(&mut screen, width, height)?;
+    for key in stdin().keys() {
+        if !browser.handle(key?) {
+            break;
+        }
+        let (width, height) = terminal_size()?;
+        browser.render(&mut screen, width, height)?;
+    }
+    Ok(())
+}
+
+/*
+ * Hides the cursor while the browser is shown. Dropping the screen shows it again before the
+ * wrapped terminal leaves the alternate screen and raw mode, on every exit path including errors.
+ */
+struct Screen<W: Write> {
+    terminal: W,
+}
+
+impl<W: Write> Screen<W> {
+    fn new(mut terminal: W) -> Result<Screen<W>, Error> {
+        write!(terminal, "{}", cursor::Hide)?;
+        Ok(Screen { terminal })
+    }
+}
+
+impl<W: Write> Write for Screen<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.terminal.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.terminal.flush()
+    }
+}
+
+impl<W: Write> Drop for Screen<W> {
+    fn drop(&mut self) {
+        let _ = write!(self.terminal, "{}", cursor::Show);
+        let _ = self.terminal.flush();
+    }
+}
+
+struct Browser {
+    host_events: Vec<HostEvents>,
+    config: Config,
+    histories: Vec<PackageHistory>,
+    query: String,
+    searching: bool,
+    selected: usize,
+    offset: usize,
+    page: usize,
+}
+
+impl Browser {
+    fn new(host_events: Vec<HostEvents>, config: Config) -> Browser {
+        let mut browser = Browser {
+            host_events,
+            config,
+            histories: Vec::new(),
+            query: String::new(),
+            searching: false,
+            selected: 0,
+            offset: 0,
+            page: 1,
+        };
+        browser.update();
+        browser
+    }
+
+    fn update(&mut self) {
+        // the query narrows the configured filters, the current results are kept while the
+        // query is not valid (yet)
+        self.config.search = match self.config.parse_search(&self.query) {
+            Ok(_) if self.query.is_empty() => None,
+            Ok(search) => Some(search),
+            Err(_) => return,
+        };
+        self.histories = host_histories(&self.host_events, &self.config);
+        self.selected = self.selected.min(self.histories.len().saturating_sub(1));
+    }
+
+    fn selected_history(&self) -> Option<&PackageHistory> {
+        self.histories.get(self.selected)
+    }
+
+    fn select(&mut self, selected: usize) {
+        self.selected = selected.min(self.histories.len().saturating_sub(1))
+    }
+
+    /*
+     * Returns false once the user wants to quit.
+     */
+    fn handle(&mut self, key: Key) -> bool {
+        if self.searching {
+            match key {
+                Key::Char('\n') | Key::Esc => self.searching = false,
+                Key::Backspace => {
+                    self.query.pop();
+                    self.update()
+                }
+                Key::Char(c) => {
+                    self.query.push(c);
+                    self.update()
+                }
+                _ => {}
+            }
+            return true;
+        }
+        match key {
+            Key::Char('q') | Key::Esc | Key::Ctrl('c') => return false,
+            Key::Char('/') => self.searching = true,
+            Key::Up | Key::Char('k') => self.select(self.selected.saturating_sub(1)),
+            Key::Down | Key::Char('j') => self.select(self.selected + 1),
+            Key::PageUp => self.select(self.selected.saturating_sub(self.page)),
+            Key::PageDown => self.select(self.selected + self.page),
+            Key::Home | Key::Char('g') => self.select(0),
+            Key::End | Key::Char('G') => self.select(self.histories.len()),
+            Key::Char('r') => {
+                self.config.with_removed = !self.config.with_removed;
+                self.config.removed_only = false;
+                self.update()
+            }
+            Key::Char('R') => {
+                self.config.removed_only = !self.config.removed_only;
+                self.config.with_removed = false;
+                self.update()
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn render<W: Write>(&mut self, screen: &mut W, width: u16, height: u16) -> Result<(), Error> {
+        let with_colors = self.config.format.with_colors();
+        let width = width as usize;
+        let rows = (height as usize).saturating_sub(2).max(1);
+        let list_width = self
+            .histories
+            .iter()
+            .map(|h| h.p.len() + 1)
+            .max()
+            .unwrap_or(1)
+            .min(width / 3)
+            .max(1);
+
+        self.page = rows;
+        if self.selected < self.offset {
+            self.offset = self.selected
+        } else if self.selected >= self.offset + rows {
+            self.offset = self.selected + 1 - rows
+        }
+
+        write!(screen, "{}", clear::All)?;
+        for (row, history) in self
+            .histories
+            .iter()
+            .enumerate()
+            .skip(self.offset)
+            .take(rows)
+        {
+            write!(
+                screen,
+                "{}",
+                cursor::Goto(1, (row - self.offset + 1) as u16)
+            )?;
+            if with_colors && is_removed(history) {
+                write!(screen, "{}", color::Fg(color::Red))?
+            }
+            if row == self.selected {
+                write!(screen, "{}", style::Invert)?
+            }
+            write!(
+                screen,
+                "{package: <list_width$}{reset}{fg_reset}",
+                package = truncate(&history.p, list_width),
+                list_width = list_width,
+                reset = style::Reset,
+                fg_reset = color::Fg(color::Reset)
+            )?
+        }
+
+        let timeline_width = width.saturating_sub(list_width + 2);
+        if let Some(history) = self.selected_history() {
+            let skip = history.e.len().saturating_sub(rows);
+            for (row, event) in history.e.iter().skip(skip).enumerate() {
+                let mut line = format!(
+                    "[{}] {} {}",
+                    event.printable_date(),
+                    event.action,
+                    event.version
+                );
+                if let Some(host) = &event.host {
+                    line.push_str(&format!(" on {}", host))
+                }
+                write!(
+                    screen,
+                    "{goto}│ {line}",
+                    goto = cursor::Goto((list_width + 1) as u16, (row + 1) as u16),
+                    line = truncate(&line, timeline_width)
+                )?
+            }
+        }
+
+        let toggle = |on: bool| if on { "on" } else { "off" };
+        let status = format!(
+            "{} packages | with removed (r): {} | removed only (R): {}",
+            self.histories.len(),
+            toggle(self.config.with_removed),
+            toggle(self.config.removed_only)
+        );
+        write!(
+            screen,
+            "{goto}{status}",
+            goto = cursor::Goto(1, (rows + 1) as u16),
+            status = truncate(&status, width)
+        )?;
+        let prompt = if self.searching || !self.query.is_empty() {
+            format!("/{}", self.query)
+        } else {
+            String::from("/ search | j/k move | q quit")
+        };
+        write!(
+            screen,
+            "{goto}{prompt}",
+            goto = cursor::Goto(1, (rows + 2) as u16),
+            prompt = truncate(&prompt, width)
+        )?;
+        screen.flush()?;
+        Ok(())
+    }
+}
+
+fn is_removed(history: &PackageHistory) -> bool {
+    match history.e.last() {
+        Some(event) => event.action.is_removed(),
+        None => false,
+    }
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    s.chars().take(width).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use crate::opt::Matching;
+    use crate::pacman::PacmanEvent;
+
+    use super::*;
+
+    fn some_host_events() -> Vec<HostEvents> {
+        vec![(None, some_pacman_events())]
+    }
+
+    fn some_pacman_events() -> Vec<PacmanEvent> {
+        vec![
+            "[2019-07-14 21:30] [ALPM] installed bash (5.0.0-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-14 21:31] [ALPM] installed linux (5.2.arch2-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-14 21:32] [ALPM] installed linux-firmware (20190628-1)"
+                .parse()
+                .unwrap(),
+            "[2019-07-14 21:33] [ALPM] removed bash (5.0.0-1)"
+                .parse()
+                .unwrap(),
+        ]
+    }
+
+    fn type_query(browser: &mut Browser, query: &str) {
+        browser.handle(Key::Char('/'));
+        for c in query.chars() {
+            browser.handle(Key::Char(c));
+        }
+        browser.handle(Key::Char('\n'));
+    }
+
+    #[test]
+    fn should_search_incrementally() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        assert_eq!(browser.histories.len(), 2);
+
+        type_query(&mut browser, "^linux$");
+        assert_eq!(browser.histories.len(), 1);
+        assert_eq!(browser.selected_history().unwrap().p, "linux");
+
+        browser.handle(Key::Char('/'));
+        browser.handle(Key::Backspace);
+        assert_eq!(browser.query, "^linux");
+        assert_eq!(browser.histories.len(), 2)
+    }
+
+    #[test]
+    fn should_keep_results_for_invalid_regex() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        type_query(&mut browser, "linux[");
+        assert_eq!(browser.histories.len(), 2)
+    }
+
+    #[test]
+    fn should_narrow_configured_filters() {
+        let mut config = Config::new();
+        config.filters = vec![Regex::new("^linux").unwrap()];
+        let mut browser = Browser::new(some_host_events(), config);
+        assert_eq!(browser.histories.len(), 2);
+
+        type_query(&mut browser, "firmware");
+        assert_eq!(browser.histories.len(), 1);
+        assert_eq!(browser.selected_history().unwrap().p, "linux-firmware");
+
+        browser.handle(Key::Char('/'));
+        for _ in 0.."firmware".len() {
+            browser.handle(Key::Backspace);
+        }
+        assert_eq!(browser.histories.len(), 2);
+        assert_eq!(browser.config.filters.len(), 1)
+    }
+
+    #[test]
+    fn should_search_with_configured_matching() {
+        let mut config = Config::new();
+        config.matching = Matching::Exact;
+        config.ignore_case = true;
+        let mut browser = Browser::new(some_host_events(), config);
+
+        type_query(&mut browser, "LINUX");
+        assert_eq!(browser.histories.len(), 1);
+        assert_eq!(browser.selected_history().unwrap().p, "linux")
+    }
+
+    #[test]
+    fn should_toggle_removed() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        browser.handle(Key::Char('r'));
+        assert_eq!(browser.histories.len(), 3);
+        browser.handle(Key::Char('R'));
+        assert!(!browser.config.with_removed);
+        assert_eq!(browser.histories.len(), 1);
+        assert_eq!(browser.selected_history().unwrap().p, "bash")
+    }
+
+    #[test]
+    fn should_clamp_selection() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        browser.handle(Key::Char('G'));
+        assert_eq!(browser.selected, 1);
+        browser.handle(Key::Down);
+        assert_eq!(browser.selected, 1);
+        browser.handle(Key::Char('g'));
+        browser.handle(Key::Up);
+        assert_eq!(browser.selected, 0)
+    }
+
+    #[test]
+    fn should_show_cursor_when_dropped() {
+        let mut terminal = Vec::new();
+        {
+            let mut screen = Screen::new(&mut terminal).unwrap();
+            write!(screen, "browser").unwrap();
+        }
+        let str = String::from_utf8(terminal).unwrap();
+        assert_eq!(str, format!("{}browser{}", cursor::Hide, cursor::Show))
+    }
+
+    #[test]
+    fn should_quit() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        assert!(browser.handle(Key::Char('j')));
+        assert!(!browser.handle(Key::Char('q')))
+    }
+
+    #[test]
+    fn should_render_list_and_timeline() {
+        let mut browser = Browser::new(some_host_events(), Config::new());
+        let mut screen = Vec::new();
+        browser.render(&mut screen, 80, 10).unwrap();
+        let str = String::from_utf8(screen).unwrap();
+        assert!(str.contains("linux"));
+        assert!(str.contains("linux-firmware"));
+        assert!(str.contains("│ [2019-07-14 21:31:00] Installed 5.2.arch2-1"));
+        assert!(str.contains("2 packages | with removed (r): off | removed only (R): off"))
+    }
+}
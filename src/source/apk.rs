@@ -0,0 +1,125 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use lazy_static::*;
+use regex::*;
+
+use crate::pacman::action::Action;
+use crate::pacman::PacmanEvent;
+use crate::source::{read_lines, LogSource, OnSkippedLine};
+
+lazy_static! {
+    static ref REGEX: Regex = Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2}[\sT]\d{2}:\d{2}:\d{2})\s+\(\d+/\d+\)\s+(?P<action>Installing|Upgrading|Downgrading|Replacing|Purging)\s+(?P<package>\S+)\s+\((?P<from>[^\s)]+)(\s->\s(?P<to>[^\s)]+))?\)").unwrap();
+}
+
+/// Alpine's `/var/log/apk.log`, apk's progress lines prefixed by their date:
+///
+/// ```text
+/// 2024-01-15 10:23:45 (1/3) Installing musl (1.2.4-r2)
+/// 2024-01-15 10:23:45 (2/3) Upgrading busybox (1.36.1-r5 -> 1.36.1-r15)
+/// 2024-01-15 10:23:46 (3/3) Purging curl (8.5.0-r0)
+/// ```
+pub struct Apk;
+
+impl LogSource for Apk {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/apk.log"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        Ok(read_lines(path, on_skipped_line)?
+            .iter()
+            .filter_map(|line| parse_line(line))
+            .collect())
+    }
+}
+
+fn parse_line(line: &str) -> Option<PacmanEvent> {
+    let captures = REGEX.captures(line)?;
+    let date =
+        NaiveDateTime::parse_from_str(&captures["date"].replace('T', " "), "%Y-%m-%d %H:%M:%S")
+            .ok()?;
+    let action = match &captures["action"] {
+        "Installing" => Action::Installed,
+        "Upgrading" => Action::Upgraded,
+        "Downgrading" => Action::Downgraded,
+        "Replacing" => Action::Reinstalled,
+        _ => Action::Removed,
+    };
+    // a replaced package may be logged as (1.0-r0 -> 1.0-r0)
+    let to = match action {
+        Action::Upgraded | Action::Downgraded => {
+            captures.name("to").map(|to| String::from(to.as_str()))
+        }
+        _ => None,
+    };
+    Some(PacmanEvent::new(
+        date,
+        action,
+        String::from(&captures["package"]),
+        String::from(&captures["from"]),
+        to,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+
+    #[test]
+    fn should_parse_install() {
+        let pacman_event =
+            parse_line("2024-01-15 10:23:45 (1/3) Installing musl (1.2.4-r2)").unwrap();
+        assert_eq!(
+            pacman_event,
+            PacmanEvent::new(
+                NaiveDateTime::new(
+                    NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                    NaiveTime::from_hms_opt(10, 23, 45).unwrap(),
+                ),
+                Action::Installed,
+                String::from("musl"),
+                String::from("1.2.4-r2"),
+                None
+            )
+        )
+    }
+
+    #[test]
+    fn should_parse_upgrade_and_downgrade() {
+        let upgrade =
+            parse_line("2024-01-15 10:23:45 (2/3) Upgrading busybox (1.36.1-r5 -> 1.36.1-r15)")
+                .unwrap();
+        assert_eq!(upgrade.action, Action::Upgraded);
+        assert_eq!(upgrade.from, "1.36.1-r5");
+        assert_eq!(upgrade.to, Some(String::from("1.36.1-r15")));
+        let downgrade =
+            parse_line("2024-01-15T10:23:45 (1/1) Downgrading curl (8.5.0-r0 -> 8.4.0-r0)")
+                .unwrap();
+        assert_eq!(downgrade.action, Action::Downgraded)
+    }
+
+    #[test]
+    fn should_parse_replace_and_purge() {
+        let replace =
+            parse_line("2024-01-15 10:23:45 (1/2) Replacing curl (8.5.0-r0 -> 8.5.0-r0)").unwrap();
+        assert_eq!(replace.action, Action::Reinstalled);
+        assert_eq!(replace.to, None);
+        let purge = parse_line("2024-01-15 10:23:46 (2/2) Purging curl (8.5.0-r0)").unwrap();
+        assert_eq!(purge.action, Action::Removed);
+        assert_eq!(purge.from, "8.5.0-r0")
+    }
+
+    #[test]
+    fn should_skip_other_lines() {
+        assert!(parse_line("(1/3) Installing musl (1.2.4-r2)").is_none());
+        assert!(parse_line("2024-01-15 10:23:46 Executing busybox-1.36.1-r15.trigger").is_none());
+        assert!(parse_line("2024-01-15 10:23:46 OK: 8 MiB in 15 packages").is_none())
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use lazy_static::*;
+use regex::*;
+
+use crate::pacman::action::Action;
+use crate::pacman::PacmanEvent;
+use crate::source::{read_lines, strip_arch, LogSource, OnSkippedLine};
+
+lazy_static! {
+    static ref ENTRY: Regex = Regex::new(r"(?P<package>[^\s,]+) \((?P<versions>[^)]*)\)").unwrap();
+}
+
+/// apt's `/var/log/apt/history.log`, one block per apt run:
+///
+/// ```text
+/// Start-Date: 2024-01-15  10:23:45
+/// Commandline: apt upgrade
+/// Upgrade: libc6:amd64 (2.36-9, 2.36-9+deb12u1), bash:amd64 (5.2.15-2, 5.2.15-2+b2)
+/// Install: foo:amd64 (1.0-1, automatic)
+/// End-Date: 2024-01-15  10:23:50
+/// ```
+pub struct Apt;
+
+impl LogSource for Apt {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/apt/history.log"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        Ok(parse(&read_lines(path, on_skipped_line)?))
+    }
+}
+
+fn parse(lines: &[String]) -> Vec<PacmanEvent> {
+    let mut pacman_events = Vec::new();
+    let mut date = None;
+    for line in lines {
+        let (key, value) = match line.split_once(": ") {
+            Some((key, value)) => (key, value.trim()),
+            None => continue,
+        };
+        let action = match key {
+            "Start-Date" => {
+                date = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").ok();
+                continue;
+            }
+            "End-Date" => {
+                date = None;
+                continue;
+            }
+            "Install" => Action::Installed,
+            "Reinstall" => Action::Reinstalled,
+            "Upgrade" => Action::Upgraded,
+            "Downgrade" => Action::Downgraded,
+            "Remove" | "Purge" => Action::Removed,
+            _ => continue,
+        };
+        let date = match date {
+            Some(date) => date,
+            None => continue,
+        };
+        for captures in ENTRY.captures_iter(value) {
+            let package = strip_arch(&captures["package"]);
+            let versions: Vec<&str> = captures["versions"].split(", ").collect();
+            let (from, to) = match action {
+                Action::Upgraded | Action::Downgraded if versions.len() > 1 => {
+                    (versions[0], Some(String::from(versions[1])))
+                }
+                _ => (versions[0], None),
+            };
+            pacman_events.push(PacmanEvent::new(
+                date,
+                action.clone(),
+                String::from(package),
+                String::from(from),
+                to,
+            ))
+        }
+    }
+    pacman_events
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+
+    #[test]
+    fn should_parse_history_log() {
+        let lines: Vec<String> = "Start-Date: 2024-01-15  10:23:45\n\
+             Commandline: apt upgrade\n\
+             Requested-By: dennis (1000)\n\
+             Install: foo:amd64 (1.0-1, automatic)\n\
+             Upgrade: libc6:amd64 (2.36-9, 2.36-9+deb12u1), bash:amd64 (5.2.15-2, 5.2.15-2+b2)\n\
+             End-Date: 2024-01-15  10:23:50\n\
+             \n\
+             Start-Date: 2024-01-16  08:00:00\n\
+             Commandline: apt purge foo\n\
+             Purge: foo:amd64 (1.0-1)\n\
+             Downgrade: bash:amd64 (5.2.15-2+b2, 5.2.15-2)\n\
+             End-Date: 2024-01-16  08:00:01"
+            .lines()
+            .map(String::from)
+            .collect();
+
+        let pacman_events = parse(&lines);
+
+        let date = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveTime::from_hms_opt(10, 23, 45).unwrap(),
+        );
+        let next_date = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2024, 1, 16).unwrap(),
+            NaiveTime::from_hms_opt(8, 0, 0).unwrap(),
+        );
+        assert_eq!(
+            pacman_events,
+            vec![
+                PacmanEvent::new(
+                    date,
+                    Action::Installed,
+                    String::from("foo"),
+                    String::from("1.0-1"),
+                    None
+                ),
+                PacmanEvent::new(
+                    date,
+                    Action::Upgraded,
+                    String::from("libc6"),
+                    String::from("2.36-9"),
+                    Some(String::from("2.36-9+deb12u1"))
+                ),
+                PacmanEvent::new(
+                    date,
+                    Action::Upgraded,
+                    String::from("bash"),
+                    String::from("5.2.15-2"),
+                    Some(String::from("5.2.15-2+b2"))
+                ),
+                PacmanEvent::new(
+                    next_date,
+                    Action::Removed,
+                    String::from("foo"),
+                    String::from("1.0-1"),
+                    None
+                ),
+                PacmanEvent::new(
+                    next_date,
+                    Action::Downgraded,
+                    String::from("bash"),
+                    String::from("5.2.15-2+b2"),
+                    Some(String::from("5.2.15-2"))
+                ),
+            ]
+        )
+    }
+
+    #[test]
+    fn should_skip_entries_without_start_date() {
+        let lines = vec![String::from("Install: foo:amd64 (1.0-1)")];
+        assert!(parse(&lines).is_empty())
+    }
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, FixedOffset};
+use lazy_static::*;
+use regex::*;
+
+use crate::pacman::action::Action;
+use crate::pacman::PacmanEvent;
+use crate::source::{read_lines, LogSource, OnSkippedLine};
+
+lazy_static! {
+    static ref REGEX: Regex = Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(Z|[+-]\d{2}:?\d{2}))\s+[A-Z]+\s+(?P<key>[A-Za-z]+):\s+(?P<nevra>\S+)\s*$").unwrap();
+}
+
+/// dnf's `/var/log/dnf.rpm.log`:
+///
+/// ```text
+/// 2024-01-15T10:23:45+0100 SUBDEBUG Upgrade: bash-5.2.26-1.fc39.x86_64
+/// 2024-01-15T10:23:46+0100 SUBDEBUG Upgraded: bash-5.2.15-1.fc39.x86_64
+/// 2024-01-15T10:23:47+0100 SUBDEBUG Installed: foo-1:1.0-1.fc39.noarch
+/// 2024-01-15T10:23:48+0100 SUBDEBUG Erase: bar-2.0-3.fc39.x86_64
+/// ```
+///
+/// Up- and downgrades are logged twice, first the new package, then the package it replaced.
+pub struct Dnf;
+
+impl LogSource for Dnf {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/dnf.rpm.log"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        Ok(parse(&read_lines(path, on_skipped_line)?))
+    }
+}
+
+fn parse(lines: &[String]) -> Vec<PacmanEvent> {
+    let mut pacman_events: Vec<PacmanEvent> = Vec::new();
+    // the index of the up- or downgrade still waiting for the version it replaced
+    let mut pending: HashMap<String, usize> = HashMap::new();
+    for line in lines {
+        let captures = match REGEX.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        let date = match parse_date(&captures["date"]) {
+            Some(date) => date,
+            None => continue,
+        };
+        let (package, version) = match split_nevra(&captures["nevra"]) {
+            Some(nevra) => nevra,
+            None => continue,
+        };
+        let action = match &captures["key"] {
+            "Install" | "Installed" | "Obsolete" => Action::Installed,
+            "Reinstall" => Action::Reinstalled,
+            "Upgrade" => Action::Upgraded,
+            "Downgrade" => Action::Downgraded,
+            "Erase" | "Obsoleted" => Action::Removed,
+            "Upgraded" | "Downgraded" => {
+                if let Some(idx) = pending.remove(package) {
+                    let pacman_event = &mut pacman_events[idx];
+                    pacman_event.to = Some(pacman_event.from.clone());
+                    pacman_event.from = String::from(version);
+                }
+                continue;
+            }
+            // the second entry of a reinstall and everything else
+            _ => continue,
+        };
+        if action == Action::Upgraded || action == Action::Downgraded {
+            pending.insert(String::from(package), pacman_events.len());
+        }
+        let mut pacman_event = PacmanEvent::new(
+            date.naive_local(),
+            action,
+            String::from(package),
+            String::from(version),
+            None,
+        );
+        pacman_event.offset = Some(*date.offset());
+        pacman_events.push(pacman_event)
+    }
+    pacman_events
+}
+
+fn parse_date(date_str: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_str(date_str, "%Y-%m-%dT%H:%M:%S%z")
+        .or_else(|_| DateTime::parse_from_rfc3339(date_str))
+        .ok()
+}
+
+/*
+ * name-[epoch:]version-release.arch, the name itself may contain dashes
+ */
+fn split_nevra(nevra: &str) -> Option<(&str, &str)> {
+    let (nevr, _arch) = nevra.rsplit_once('.')?;
+    let (nev, _release) = nevr.rsplit_once('-')?;
+    let (name, _version) = nev.rsplit_once('-')?;
+    Some((name, &nevr[name.len() + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    use super::*;
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn should_split_nevra() {
+        assert_eq!(
+            split_nevra("bash-5.2.26-1.fc39.x86_64"),
+            Some(("bash", "5.2.26-1.fc39"))
+        );
+        assert_eq!(
+            split_nevra("python3-dnf-plugins-core-1:4.4.4-1.fc39.noarch"),
+            Some(("python3-dnf-plugins-core", "1:4.4.4-1.fc39"))
+        );
+        assert_eq!(split_nevra("garbage"), None)
+    }
+
+    #[test]
+    fn should_parse_dnf_rpm_log() {
+        let pacman_events = parse(&lines(
+            "2024-01-15T10:23:45+0100 SUBDEBUG Upgrade: bash-5.2.26-1.fc39.x86_64\n\
+             2024-01-15T10:23:45+0100 SUBDEBUG Installed: foo-1:1.0-1.fc39.noarch\n\
+             2024-01-15T10:23:46+0100 SUBDEBUG Upgraded: bash-5.2.15-1.fc39.x86_64\n\
+             2024-01-15T10:23:47+0100 SUBDEBUG Erase: bar-2.0-3.fc39.x86_64\n\
+             2024-01-15T10:23:48+0100 INFO --- logging initialized ---",
+        ));
+
+        let date = NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveTime::from_hms_opt(10, 23, 45).unwrap(),
+        );
+        let offset = FixedOffset::east_opt(3600);
+        assert_eq!(pacman_events.len(), 3);
+        assert_eq!(
+            pacman_events[0],
+            PacmanEvent {
+                date,
+                action: Action::Upgraded,
+                package: String::from("bash"),
+                from: String::from("5.2.15-1.fc39"),
+                to: Some(String::from("5.2.26-1.fc39")),
+                offset,
+            }
+        );
+        assert_eq!(
+            pacman_events[1],
+            PacmanEvent {
+                date,
+                action: Action::Installed,
+                package: String::from("foo"),
+                from: String::from("1:1.0-1.fc39"),
+                to: None,
+                offset,
+            }
+        );
+        assert_eq!(pacman_events[2].action, Action::Removed);
+        assert_eq!(pacman_events[2].package, "bar")
+    }
+
+    #[test]
+    fn should_parse_downgrade_and_reinstall() {
+        let pacman_events = parse(&lines(
+            "2024-01-15T09:23:45Z DEBUG Downgrade: vim-enhanced-2:9.0.2081-1.fc39.x86_64\n\
+             2024-01-15T09:23:46Z DEBUG Downgraded: vim-enhanced-2:9.1.031-1.fc39.x86_64\n\
+             2024-01-15T09:23:47Z DEBUG Reinstall: feh-3.10-1.fc39.x86_64\n\
+             2024-01-15T09:23:48Z DEBUG Reinstalled: feh-3.10-1.fc39.x86_64",
+        ));
+
+        assert_eq!(pacman_events.len(), 2);
+        assert_eq!(pacman_events[0].action, Action::Downgraded);
+        assert_eq!(pacman_events[0].from, "2:9.1.031-1.fc39");
+        assert_eq!(pacman_events[0].to, Some(String::from("2:9.0.2081-1.fc39")));
+        assert_eq!(pacman_events[0].offset, FixedOffset::east_opt(0));
+        assert_eq!(pacman_events[1].action, Action::Reinstalled);
+        assert_eq!(pacman_events[1].from, "3.10-1.fc39")
+    }
+}
@@ -0,0 +1,229 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use chrono::NaiveDateTime;
+use lazy_static::*;
+use regex::*;
+
+use crate::pacman::action::Action;
+use crate::pacman::PacmanEvent;
+use crate::source::{read_lines, strip_arch, LogSource, OnSkippedLine};
+
+lazy_static! {
+    static ref REGEX: Regex = Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2}\s\d{2}:\d{2}:\d{2})\s(?P<action>install|upgrade|remove)\s(?P<package>\S+)\s(?P<from>\S+)\s(?P<to>\S+)$").unwrap();
+}
+
+/// dpkg's `/var/log/dpkg.log`, e.g. `2024-01-15 10:23:46 upgrade libc6:amd64 2.36-9 2.36-9+deb12u1`.
+///
+/// dpkg logs upgrades, downgrades and reinstalls alike, they are told apart by comparing the versions.
+/// Purges are skipped, they always follow the removal of the package.
+pub struct Dpkg;
+
+impl LogSource for Dpkg {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/dpkg.log"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        Ok(read_lines(path, on_skipped_line)?
+            .iter()
+            .filter_map(|line| parse_line(line))
+            .collect())
+    }
+}
+
+fn parse_line(line: &str) -> Option<PacmanEvent> {
+    let captures = REGEX.captures(line)?;
+    let date = NaiveDateTime::parse_from_str(&captures["date"], "%Y-%m-%d %H:%M:%S").ok()?;
+    let package = String::from(strip_arch(&captures["package"]));
+    let from = &captures["from"];
+    let to = &captures["to"];
+    let (action, from, to) = match (&captures["action"], from, to) {
+        ("remove", from, _) => (Action::Removed, from, None),
+        (_, "<none>", to) => (Action::Installed, to, None),
+        (_, from, to) => {
+            let action = match compare_versions(to, from) {
+                Ordering::Greater => Action::Upgraded,
+                Ordering::Less => Action::Downgraded,
+                Ordering::Equal => Action::Reinstalled,
+            };
+            if action == Action::Reinstalled {
+                (action, to, None)
+            } else {
+                (action, from, Some(String::from(to)))
+            }
+        }
+    };
+    Some(PacmanEvent::new(
+        date,
+        action,
+        package,
+        String::from(from),
+        to,
+    ))
+}
+
+/// Compares two Debian versions (`[epoch:]upstream[-revision]`) the way dpkg does.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let (a_epoch, a_upstream, a_revision) = split_version(a);
+    let (b_epoch, b_upstream, b_revision) = split_version(b);
+    a_epoch
+        .cmp(&b_epoch)
+        .then_with(|| compare_parts(a_upstream, b_upstream))
+        .then_with(|| compare_parts(a_revision, b_revision))
+}
+
+fn split_version(version: &str) -> (u64, &str, &str) {
+    let (epoch, rest) = match version.split_once(':') {
+        Some((epoch, rest)) => (epoch.parse().unwrap_or(0), rest),
+        None => (0, version),
+    };
+    match rest.rsplit_once('-') {
+        Some((upstream, revision)) => (epoch, upstream, revision),
+        None => (epoch, rest, ""),
+    }
+}
+
+/*
+ * '~' sorts before everything (even the end of the version), letters before other characters
+ */
+fn order(c: Option<u8>) -> i32 {
+    match c {
+        None => 0,
+        Some(c) if c.is_ascii_digit() => 0,
+        Some(c) if c.is_ascii_alphabetic() => c as i32,
+        Some(b'~') => -1,
+        Some(c) => c as i32 + 256,
+    }
+}
+
+/*
+ * Alternately compares the non-digit prefixes character by character and the following numbers by value
+ */
+fn compare_parts(a: &str, b: &str) -> Ordering {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        while (i < a.len() && !a[i].is_ascii_digit()) || (j < b.len() && !b[j].is_ascii_digit()) {
+            let (ac, bc) = (order(a.get(i).copied()), order(b.get(j).copied()));
+            if ac != bc {
+                return ac.cmp(&bc);
+            }
+            i += 1;
+            j += 1;
+        }
+        while i < a.len() && a[i] == b'0' {
+            i += 1
+        }
+        while j < b.len() && b[j] == b'0' {
+            j += 1
+        }
+        let mut first_diff = Ordering::Equal;
+        while i < a.len() && a[i].is_ascii_digit() && j < b.len() && b[j].is_ascii_digit() {
+            if first_diff == Ordering::Equal {
+                first_diff = a[i].cmp(&b[j])
+            }
+            i += 1;
+            j += 1;
+        }
+        if i < a.len() && a[i].is_ascii_digit() {
+            return Ordering::Greater;
+        }
+        if j < b.len() && b[j].is_ascii_digit() {
+            return Ordering::Less;
+        }
+        if first_diff != Ordering::Equal {
+            return first_diff;
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::*;
+
+    fn some_date() -> NaiveDateTime {
+        NaiveDateTime::new(
+            NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+            NaiveTime::from_hms_opt(10, 23, 46).unwrap(),
+        )
+    }
+
+    #[test]
+    fn should_parse_install() {
+        let pacman_event =
+            parse_line("2024-01-15 10:23:46 install foo:amd64 <none> 1.0-1").unwrap();
+        assert_eq!(
+            pacman_event,
+            PacmanEvent::new(
+                some_date(),
+                Action::Installed,
+                String::from("foo"),
+                String::from("1.0-1"),
+                None
+            )
+        )
+    }
+
+    #[test]
+    fn should_parse_upgrade() {
+        let pacman_event =
+            parse_line("2024-01-15 10:23:46 upgrade libc6:amd64 2.36-9 2.36-9+deb12u1").unwrap();
+        assert_eq!(
+            pacman_event,
+            PacmanEvent::new(
+                some_date(),
+                Action::Upgraded,
+                String::from("libc6"),
+                String::from("2.36-9"),
+                Some(String::from("2.36-9+deb12u1"))
+            )
+        )
+    }
+
+    #[test]
+    fn should_parse_downgrade_and_reinstall() {
+        let downgrade = parse_line("2024-01-15 10:23:46 upgrade bash:amd64 5.2-2 5.1-1").unwrap();
+        assert_eq!(downgrade.action, Action::Downgraded);
+        let reinstall = parse_line("2024-01-15 10:23:46 install bash:amd64 5.2-2 5.2-2").unwrap();
+        assert_eq!(reinstall.action, Action::Reinstalled);
+        assert_eq!(reinstall.to, None)
+    }
+
+    #[test]
+    fn should_parse_remove() {
+        let pacman_event = parse_line("2024-01-15 10:23:46 remove foo:amd64 1.0-1 <none>").unwrap();
+        assert_eq!(pacman_event.action, Action::Removed);
+        assert_eq!(pacman_event.from, "1.0-1")
+    }
+
+    #[test]
+    fn should_skip_other_lines() {
+        assert!(parse_line("2024-01-15 10:23:46 status installed foo:amd64 1.0-1").is_none());
+        assert!(parse_line("2024-01-15 10:23:46 purge foo:amd64 1.0-1 <none>").is_none());
+        assert!(parse_line("2024-01-15 10:23:46 startup packages configure").is_none())
+    }
+
+    #[test]
+    fn should_compare_versions() {
+        assert_eq!(compare_versions("1.0-1", "1.0-1"), Ordering::Equal);
+        assert_eq!(compare_versions("1.0-2", "1.0-1"), Ordering::Greater);
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1:0.9", "2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0~rc1", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0a", "1.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0+b1", "1.0a"), Ordering::Greater);
+        assert_eq!(
+            compare_versions("2.36-9+deb12u1", "2.36-9"),
+            Ordering::Greater
+        );
+        assert_eq!(compare_versions("1.001", "1.1"), Ordering::Equal)
+    }
+}
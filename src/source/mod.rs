@@ -0,0 +1,271 @@
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorDetail};
+use crate::pacman::{Pacman, PacmanEvent};
+use crate::source::apk::Apk;
+use crate::source::apt::Apt;
+use crate::source::dnf::Dnf;
+use crate::source::dpkg::Dpkg;
+use crate::source::xbps::Xbps;
+
+pub mod apk;
+pub mod apt;
+pub mod dnf;
+pub mod dpkg;
+pub mod xbps;
+
+/// A package manager log that can be read as a list of events.
+///
+/// Every source normalizes its entries into `PacmanEvent`s, so filters, groups and outputs work the same for all of them.
+pub trait LogSource {
+    /// The logfile read if none is given
+    fn default_logfile(&self) -> &'static str;
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>>;
+
+    /// Reads the events of the given logfile, failing with `ErrorDetail::UnreadableLogfile`.
+    fn read_logfile(
+        &self,
+        logfile: &str,
+        on_skipped_line: OnSkippedLine,
+    ) -> Result<Vec<PacmanEvent>, Error> {
+        let pacman_events = self
+            .read_file(Path::new(logfile), on_skipped_line)
+            .map_err(|e| {
+                Error::new(ErrorDetail::UnreadableLogfile {
+                    path: String::from(logfile),
+                    msg: e.to_string(),
+                })
+            })?;
+        Ok(without_invalid_versions(
+            logfile,
+            pacman_events,
+            on_skipped_line,
+        ))
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Source {
+    Pacman,
+    Apt,
+    Dpkg,
+    Dnf,
+    Apk,
+    Xbps,
+}
+
+impl Source {
+    pub fn log_source(&self) -> &'static dyn LogSource {
+        match self {
+            Source::Pacman => &Pacman,
+            Source::Apt => &Apt,
+            Source::Dpkg => &Dpkg,
+            Source::Dnf => &Dnf,
+            Source::Apk => &Apk,
+            Source::Xbps => &Xbps,
+        }
+    }
+}
+
+impl FromStr for Source {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "pacman" => Ok(Source::Pacman),
+            "apt" => Ok(Source::Apt),
+            "dpkg" => Ok(Source::Dpkg),
+            "dnf" => Ok(Source::Dnf),
+            "apk" => Ok(Source::Apk),
+            "xbps" => Ok(Source::Xbps),
+            _ => Err(Error::new(ErrorDetail::InvalidConfig {
+                msg: format!("Unknown source '{}'", s),
+            })),
+        }
+    }
+}
+
+/// A line of a logfile that couldn't be read and was skipped.
+#[derive(Debug, PartialEq)]
+pub struct SkippedLine {
+    pub path: String,
+    /// `None` if the line is no longer known, e.g. for an event with an invalid version
+    pub line: Option<usize>,
+    pub msg: String,
+}
+
+impl SkippedLine {
+    pub fn new(path: &Path, idx: usize, msg: String) -> SkippedLine {
+        SkippedLine {
+            path: path.display().to_string(),
+            line: Some(idx + 1),
+            msg,
+        }
+    }
+}
+
+impl fmt::Display for SkippedLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Skipping line {}:{} ({})", self.path, line, self.msg),
+            None => write!(f, "Skipping an event of {} ({})", self.path, self.msg),
+        }
+    }
+}
+
+/// Called for every skipped line, the library itself doesn't print them.
+pub type OnSkippedLine = fn(&SkippedLine);
+
+/*
+ * The sources split the versions off the logged lines, e.g. xbps' `foo-` leaves an empty version
+ */
+fn without_invalid_versions(
+    logfile: &str,
+    pacman_events: Vec<PacmanEvent>,
+    on_skipped_line: OnSkippedLine,
+) -> Vec<PacmanEvent> {
+    pacman_events
+        .into_iter()
+        .filter(|pacman_event| match pacman_event.versions() {
+            Ok(_) => true,
+            Err(e) => {
+                on_skipped_line(&SkippedLine {
+                    path: String::from(logfile),
+                    line: None,
+                    msg: format!("{} of {}", e.detail(), pacman_event.package),
+                });
+                false
+            }
+        })
+        .collect()
+}
+
+/// Reads the lines of the given file, unreadable lines are skipped.
+pub fn read_lines(path: &Path, on_skipped_line: OnSkippedLine) -> std::io::Result<Vec<String>> {
+    let file = BufReader::new(File::open(path)?);
+    let mut lines = Vec::new();
+    for (idx, line) in file.lines().enumerate() {
+        match line {
+            Ok(line) => lines.push(line),
+            Err(e) => on_skipped_line(&SkippedLine::new(path, idx, e.to_string())),
+        }
+    }
+    Ok(lines)
+}
+
+/*
+ * Debian packages are qualified by their architecture (libc6:amd64), the other sources don't know it
+ */
+fn strip_arch(package: &str) -> &str {
+    match package.split_once(':') {
+        Some((package, _)) => package,
+        None => package,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io::Write;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    static SKIPPED_LINE: AtomicUsize = AtomicUsize::new(0);
+    static SKIPPED_EVENTS: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn should_report_skipped_lines() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        file.write_all(b"first\n\xff\xfe\nthird\n").unwrap();
+
+        let lines = read_lines(Path::new(&file_name), |skipped_line| {
+            SKIPPED_LINE.store(skipped_line.line.unwrap(), Ordering::SeqCst)
+        })
+        .unwrap();
+
+        assert_eq!(lines, vec!["first", "third"]);
+        assert_eq!(SKIPPED_LINE.load(Ordering::SeqCst), 2);
+        fs::remove_file(&file_name).unwrap()
+    }
+
+    #[test]
+    fn should_skip_events_with_invalid_versions() {
+        let file_name = uuid::Uuid::new_v4().to_string();
+        let mut file = File::create(&file_name).unwrap();
+        writeln!(
+            file,
+            "2024-01-15 10:23:46 foo-: installed successfully.\n\
+             2024-01-15 10:23:47 bar-1.0_1: installed successfully."
+        )
+        .unwrap();
+
+        let pacman_events = Source::Xbps
+            .log_source()
+            .read_logfile(&file_name, |skipped_line| {
+                assert_eq!(skipped_line.line, None);
+                SKIPPED_EVENTS.fetch_add(1, Ordering::SeqCst);
+            })
+            .unwrap();
+
+        assert_eq!(pacman_events.len(), 1);
+        assert_eq!(pacman_events[0].package, "bar");
+        assert_eq!(SKIPPED_EVENTS.load(Ordering::SeqCst), 1);
+        fs::remove_file(&file_name).unwrap()
+    }
+
+    #[test]
+    fn should_parse_source() {
+        assert_eq!("pacman".parse::<Source>().unwrap(), Source::Pacman);
+        assert_eq!("APT".parse::<Source>().unwrap(), Source::Apt);
+        assert_eq!("dpkg".parse::<Source>().unwrap(), Source::Dpkg);
+        assert_eq!("dnf".parse::<Source>().unwrap(), Source::Dnf);
+        assert_eq!("apk".parse::<Source>().unwrap(), Source::Apk);
+        assert_eq!("xbps".parse::<Source>().unwrap(), Source::Xbps);
+        assert!("yum".parse::<Source>().is_err())
+    }
+
+    #[test]
+    fn should_use_default_logfiles() {
+        assert_eq!(
+            Source::Pacman.log_source().default_logfile(),
+            "/var/log/pacman.log"
+        );
+        assert_eq!(
+            Source::Apt.log_source().default_logfile(),
+            "/var/log/apt/history.log"
+        );
+        assert_eq!(
+            Source::Dpkg.log_source().default_logfile(),
+            "/var/log/dpkg.log"
+        );
+        assert_eq!(
+            Source::Dnf.log_source().default_logfile(),
+            "/var/log/dnf.rpm.log"
+        );
+        assert_eq!(
+            Source::Apk.log_source().default_logfile(),
+            "/var/log/apk.log"
+        );
+        assert_eq!(
+            Source::Xbps.log_source().default_logfile(),
+            "/var/log/socklog/everything/current"
+        )
+    }
+
+    #[test]
+    fn should_strip_arch() {
+        assert_eq!(strip_arch("libc6:amd64"), "libc6");
+        assert_eq!(strip_arch("bash"), "bash")
+    }
+}
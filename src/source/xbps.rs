@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, NaiveDateTime};
+use lazy_static::*;
+use regex::*;
+
+use crate::pacman::action::Action;
+use crate::pacman::PacmanEvent;
+use crate::source::{read_lines, LogSource, OnSkippedLine};
+
+lazy_static! {
+    static ref REGEX: Regex = Regex::new(r"^(?P<date>\d{4}-\d{2}-\d{2}[\sT_]\d{2}:\d{2}:\d{2})(\.\d+)?(?P<offset>Z|[+-]\d{2}:?\d{2})?\s.*?((?P<syslog_action>Installed|Updated|Removed)\s`(?P<syslog_pkgver>[^']+)'\ssuccessfully|(?P<pkgver>\S+):\s(?P<action>installed|updated|removed)\ssuccessfully)").unwrap();
+}
+
+/// xbps' syslog messages as written by socklog, or timestamped `xbps-install` output:
+///
+/// ```text
+/// 2024-01-15T10:23:45.12345 user.notice: xbps-install: Installed `foo-1.0_1' successfully (rootdir: /).
+/// 2024-01-15T10:23:46.12345 user.notice: xbps-install: Updated `bash-5.2.26_1' successfully (rootdir: /).
+/// 2024-01-15 10:23:47 foo-1.0_1: removed successfully.
+/// ```
+///
+/// xbps only logs the new version of an update, the version it replaced is the last one seen for the package.
+pub struct Xbps;
+
+impl LogSource for Xbps {
+    fn default_logfile(&self) -> &'static str {
+        "/var/log/socklog/everything/current"
+    }
+
+    fn read_file(
+        &self,
+        path: &Path,
+        on_skipped_line: OnSkippedLine,
+    ) -> std::io::Result<Vec<PacmanEvent>> {
+        Ok(parse(&read_lines(path, on_skipped_line)?))
+    }
+}
+
+fn parse(lines: &[String]) -> Vec<PacmanEvent> {
+    let mut pacman_events = Vec::new();
+    let mut versions: HashMap<String, String> = HashMap::new();
+    for line in lines {
+        let captures = match REGEX.captures(line) {
+            Some(captures) => captures,
+            None => continue,
+        };
+        let date_str = captures["date"].replace(['T', '_'], " ");
+        let (date, offset) = match captures.name("offset") {
+            Some(offset) => match DateTime::parse_from_str(
+                &format!("{}{}", date_str, offset.as_str().replace('Z', "+0000")),
+                "%Y-%m-%d %H:%M:%S%z",
+            ) {
+                Ok(date_time) => (date_time.naive_local(), Some(*date_time.offset())),
+                Err(_) => continue,
+            },
+            None => match NaiveDateTime::parse_from_str(&date_str, "%Y-%m-%d %H:%M:%S") {
+                Ok(date) => (date, None),
+                Err(_) => continue,
+            },
+        };
+        let (action, pkgver) = match (captures.name("syslog_action"), captures.name("action")) {
+            (Some(action), _) => (action.as_str(), &captures["syslog_pkgver"]),
+            (None, Some(action)) => (action.as_str(), &captures["pkgver"]),
+            (None, None) => continue,
+        };
+        let (package, version) = match pkgver.rsplit_once('-') {
+            Some(pkgver) => pkgver,
+            None => continue,
+        };
+        let previous = versions.insert(String::from(package), String::from(version));
+        let (action, from, to) = match (action.to_lowercase().as_str(), previous) {
+            ("removed", _) => {
+                versions.remove(package);
+                (Action::Removed, String::from(version), None)
+            }
+            (_, Some(previous)) if previous == version => {
+                (Action::Reinstalled, String::from(version), None)
+            }
+            ("updated", Some(previous)) => {
+                (Action::Upgraded, previous, Some(String::from(version)))
+            }
+            // the version an update replaced may have been installed before the log begins
+            ("updated", None) => (Action::Upgraded, String::from(version), None),
+            _ => (Action::Installed, String::from(version), None),
+        };
+        let mut pacman_event = PacmanEvent::new(date, action, String::from(package), from, to);
+        pacman_event.offset = offset;
+        pacman_events.push(pacman_event)
+    }
+    pacman_events
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{FixedOffset, NaiveDate, NaiveTime};
+
+    use super::*;
+
+    fn lines(s: &str) -> Vec<String> {
+        s.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn should_parse_syslog_messages() {
+        let pacman_events = parse(&lines(
+            "2024-01-15T10:23:45.12345 user.notice: xbps-install: Installed `bash-5.2.21_1' successfully (rootdir: /).\n\
+             2024-01-15T10:23:46.12345 user.notice: xbps-install: Updated `bash-5.2.26_1' successfully (rootdir: /).\n\
+             2024-01-15T10:23:47.12345 user.notice: xbps-install: Installed `bash-5.2.26_1' successfully (rootdir: /).\n\
+             2024-01-15T10:23:48.12345 user.notice: xbps-remove: Removed `bash-5.2.26_1' successfully (rootdir: /).\n\
+             2024-01-15T10:23:49.12345 daemon.info: dhcpcd[123]: eth0: leased 10.0.0.2",
+        ));
+
+        assert_eq!(pacman_events.len(), 4);
+        assert_eq!(
+            pacman_events[0],
+            PacmanEvent::new(
+                NaiveDateTime::new(
+                    NaiveDate::from_ymd_opt(2024, 1, 15).unwrap(),
+                    NaiveTime::from_hms_opt(10, 23, 45).unwrap(),
+                ),
+                Action::Installed,
+                String::from("bash"),
+                String::from("5.2.21_1"),
+                None
+            )
+        );
+        assert_eq!(pacman_events[1].action, Action::Upgraded);
+        assert_eq!(pacman_events[1].from, "5.2.21_1");
+        assert_eq!(pacman_events[1].to, Some(String::from("5.2.26_1")));
+        assert_eq!(pacman_events[2].action, Action::Reinstalled);
+        assert_eq!(pacman_events[3].action, Action::Removed);
+        assert_eq!(pacman_events[3].from, "5.2.26_1")
+    }
+
+    #[test]
+    fn should_parse_xbps_install_output() {
+        let pacman_events = parse(&lines(
+            "2024-01-15 10:23:45+0100 libfoo-devel-1.0_1: unpacking ...\n\
+             2024-01-15 10:23:45+0100 libfoo-devel-1.0_1: installed successfully.\n\
+             2024-01-15 10:23:46+0100 libfoo-devel-1.1_1: updated successfully.",
+        ));
+
+        assert_eq!(pacman_events.len(), 2);
+        assert_eq!(pacman_events[0].package, "libfoo-devel");
+        assert_eq!(pacman_events[0].action, Action::Installed);
+        assert_eq!(pacman_events[0].offset, FixedOffset::east_opt(3600));
+        assert_eq!(pacman_events[1].action, Action::Upgraded);
+        assert_eq!(pacman_events[1].from, "1.0_1")
+    }
+
+    #[test]
+    fn should_parse_update_without_previous_version() {
+        let pacman_events = parse(&lines(
+            "2024-01-15T10:23:46Z xbps-install: Updated `bash-5.2.26_1' successfully (rootdir: /).",
+        ));
+
+        assert_eq!(pacman_events[0].action, Action::Upgraded);
+        assert_eq!(pacman_events[0].from, "5.2.26_1");
+        assert_eq!(pacman_events[0].to, None);
+        assert_eq!(pacman_events[0].offset, FixedOffset::east_opt(0))
+    }
+}